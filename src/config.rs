@@ -1,3 +1,80 @@
+use std::fs;
+use std::io;
+
+use crate::ArchiveInsertion;
+use crate::SelectionMethod;
+
+/// # WeightMutation
+/// How `Genome::mutation` changes the weights of a genome
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightMutation {
+    // Each gene is perturbed by adding a N(0, sigma) sample, or replaced
+    Gaussian,
+    // Each gene is perturbed by multiplying it with a N(0, 1) sample, or replaced.
+    // With self-adaptive mutation the sample is 1 + N(0, sigma) instead, scaled by the genome's own sigma
+    Multiplicative,
+    // A single random gene is perturbed strongly, with sigma scaled by the square root of the number of genes
+    OneGene,
+}
+
+impl WeightMutation {
+    /// # from_name
+    /// Parses the name used in config files
+    pub fn from_name(name: &str) -> Option<WeightMutation> {
+        match name {
+            "gaussian" => Some(WeightMutation::Gaussian),
+            "multiplicative" => Some(WeightMutation::Multiplicative),
+            "one-gene" => Some(WeightMutation::OneGene),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            WeightMutation::Gaussian => "gaussian",
+            WeightMutation::Multiplicative => "multiplicative",
+            WeightMutation::OneGene => "one-gene",
+        }
+    }
+}
+
+/// # CrossoverMethod
+/// How `Genome::crossover_with` combines the genes of two parents
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrossoverMethod {
+    // Each matching gene comes from a random parent
+    Uniform,
+    // Each matching gene gets the mean weight of both parents
+    Averaging,
+    // The genes in innovation order are cut in two, the second part comes from the other parent
+    SinglePoint,
+    // The genes in innovation order are cut at crossover_points places, alternating between the parents
+    MultiPoint,
+}
+
+impl CrossoverMethod {
+    /// # from_name
+    /// Parses the name used in config files
+    pub fn from_name(name: &str) -> Option<CrossoverMethod> {
+        match name {
+            "uniform" => Some(CrossoverMethod::Uniform),
+            "averaging" => Some(CrossoverMethod::Averaging),
+            "single-point" => Some(CrossoverMethod::SinglePoint),
+            "multi-point" => Some(CrossoverMethod::MultiPoint),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CrossoverMethod::Uniform => "uniform",
+            CrossoverMethod::Averaging => "averaging",
+            CrossoverMethod::SinglePoint => "single-point",
+            CrossoverMethod::MultiPoint => "multi-point",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Config {
     /***
@@ -14,12 +91,31 @@ pub struct Config {
      * constant used in genomic distance calculation - this is the weight of average connection weight difference
      */
     pub c3: f32,
+
+    /**
+     * genomes with at least this many connection genes divide the excess and disjoint terms by their size, smaller genomes use 1
+     */
+    pub compatibility_normalisation_threshold: usize,
     
     /**
      * genomic distance we allow before two genomes are in seperate species - two genomes belong to the same species if genomic difference is less than this number
      */
     pub dt: f32,
 
+    /**
+     * Adjust the compatibility threshold after every generation to steer the number of species towards target_species_count,
+     * dt is the threshold of the first generation
+     */
+    pub dynamic_dt: bool,
+    pub target_species_count: usize,
+
+    /**
+     * Amount the dynamic threshold changes by per generation, and the bounds it is clamped to
+     */
+    pub dt_step: f32,
+    pub dt_min: f32,
+    pub dt_max: f32,
+
     /**
      * Fraction of children genomes resulting from mutation without crossover. The remaining children come from mating with corssover.
      */
@@ -35,12 +131,67 @@ pub struct Config {
      * Each child has a MUTATION_RATE chance of mutating in each generation
      */
     pub pertrubing_rate: f32,
+
+    /**
+     * Operator used to mutate the weights of a child
+     */
+    pub weight_mutation: WeightMutation,
+
+    /**
+     * Chance of each weight being mutated when a child has its weights mutated, unused by the one gene operator
+     */
+    pub weight_mutation_gene_rate: f32,
+
+    /**
+     * Standard deviation of the gaussian perturbation of a weight
+     */
+    pub weight_perturbation_sigma: f32,
+
+    /**
+     * Weights that are replaced get a new value between -weight_replace_range and weight_replace_range, it has to be positive
+     */
+    pub weight_replace_range: f32,
+
+    /**
+     * Bounds every mutated weight and every new connection weight is clamped to, weights are unbounded by default
+     */
+    pub weight_min: f32,
+    pub weight_max: f32,
+
+    /**
+     * Every genome carries its own perturbation sigma, mutated log-normally before its weights are mutated
+     * and averaged between the parents in crossover. Replaces weight_perturbation_sigma, and with multiplicative
+     * weight mutation the fixed N(0, 1) scale
+     */
+    pub self_adaptive_mutation: bool,
+
+    /**
+     * Bounds the self-adaptive sigma of a genome is clamped to
+     */
+    pub mutation_step_min: f32,
+    pub mutation_step_max: f32,
     
     /**
      * Chance of a weight being disabled if it is disabled in either parent
      */
     pub disabled_gene_inheriting_chance: f32,
     
+    /**
+     * Operator used to combine the genes of two parents
+     */
+    pub crossover: CrossoverMethod,
+
+    /**
+     * Number of cuts made by multi-point crossover
+     */
+    pub crossover_points: usize,
+
+    /**
+     * When both parents are equally fit the disjoint and excess genes of both are inherited, instead of only those of one parent.
+     * Ties are common with sparse rewards
+     */
+    pub crossover_equal_fitness_inherits_both: bool,
+
     /**
      * Chance of mutating a child in a way that adds a node to the genome.
      */
@@ -50,12 +201,107 @@ pub struct Config {
      * Chance of mutating a child in a way that adds a connection to the genome.
      */
     pub add_node_rate: f32,
+
+    /**
+     * Chance of mutating a child in a way that enables a disabled connection or disables an enabled one,
+     * with phased search only while complexifying
+     */
+    pub toggle_enable_rate: f32,
+
+    /**
+     * Chance of mutating a child in a way that removes a connection from the genome, 0 keeps genomes growing only
+     */
+    pub delete_connection_rate: f32,
+
+    /**
+     * Chance of mutating a child in a way that removes a hidden node and its connections from the genome
+     */
+    pub delete_node_rate: f32,
     
     /**
      * Percentage of offspring generated using crossover of two parents - the rest comes from asexual mutation
      */
     pub offspring_from_crossover: f32,
 
+    /**
+     * Chance of the second parent being picked from another species than the first parent, instead of the same one
+     */
+    pub interspecies_mating_rate: f32,
+
+    /**
+     * Species whose best fitness hasn't improved for this many generations stop reproducing,
+     * 0 keeps every species
+     */
+    pub species_stagnation_generations: usize,
+
+    /**
+     * Number of the fittest genomes copied unchanged into the next generation
+     */
+    pub elitism_count: usize,
+
+    /**
+     * Also copy the champion of every species with more than species_elitism_min_size members unchanged, as in the original NEAT
+     */
+    pub species_elitism: bool,
+    pub species_elitism_min_size: usize,
+
+    /**
+     * How the parents of the next generation are picked
+     */
+    pub selection: SelectionMethod,
+
+    /**
+     * Fraction of the fittest genomes allowed to reproduce with truncation selection
+     */
+    pub survival_fraction: f32,
+
+    /**
+     * Number of genomes competing in each tournament with tournament selection
+     */
+    pub tournament_size: usize,
+
+    /**
+     * Select genomes by how novel their behaviour is, see FitnessGenomeProvider::fitness_and_behaviour.
+     * The novelty of a genome is its mean behaviour distance to its novelty_k nearest neighbours in the population and the archive
+     */
+    pub novelty_search: bool,
+    pub novelty_k: usize,
+
+    /**
+     * Genomes are selected by novelty_fitness_blend * novelty + (1 - novelty_fitness_blend) * fitness,
+     * 1 is pure novelty search and 0 pure fitness. Both should be on a similar scale when blending
+     */
+    pub novelty_fitness_blend: f32,
+
+    /**
+     * Which behaviours of each generation are archived, and the parameter of each rule
+     */
+    pub novelty_archive_insertion: ArchiveInsertion,
+    pub novelty_archive_threshold: f32,
+    pub novelty_archive_probability: f32,
+    pub novelty_archive_per_generation: usize,
+
+    /**
+     * The oldest behaviours are dropped once the archive grows past this size
+     */
+    pub novelty_archive_max_size: usize,
+
+    /**
+     * Alternate between a complexifying phase and a simplifying phase that only uses the delete and weight mutations,
+     * the delete rates have to be above zero for the simplifying phase to prune anything
+     */
+    pub phased_search: bool,
+
+    /**
+     * The simplifying phase starts once the mean number of connections grows this much above where the complexifying phase started
+     */
+    pub phased_search_complexity_threshold: f32,
+
+    /**
+     * The complexifying phase starts again once the mean number of connections hasn't fallen for this many generations
+     */
+    pub phased_search_stall_generations: usize,
+
     population_size: usize,
     generation_count: usize,
 }
@@ -66,21 +312,73 @@ impl Config {
             c1: 1.0,
             c2: 1.0,
             c3: 0.4,
+            compatibility_normalisation_threshold: 20,
             dt: 3.0,
 
+            dynamic_dt: false,
+            target_species_count: 10,
+            dt_step: 0.3,
+            dt_min: 0.3,
+            dt_max: 30.0,
+
             a_sexual_reproduction_rate: 0.25,
 
             mutation_rate: 0.8,
 
             pertrubing_rate: 0.9,
+
+            weight_mutation: WeightMutation::Multiplicative,
+            weight_mutation_gene_rate: 1.0,
+            weight_perturbation_sigma: 0.5,
+            weight_replace_range: 2.0,
+            weight_min: f32::NEG_INFINITY,
+            weight_max: f32::INFINITY,
+
+            self_adaptive_mutation: false,
+            mutation_step_min: 0.01,
+            mutation_step_max: 4.0,
             
             disabled_gene_inheriting_chance: 0.30,
 
+            crossover: CrossoverMethod::Uniform,
+            crossover_points: 2,
+            crossover_equal_fitness_inherits_both: false,
+
             add_connection_rate: 0.05,
             add_node_rate: 0.03,
 
+            toggle_enable_rate: 0.0,
+
+            delete_connection_rate: 0.0,
+            delete_node_rate: 0.0,
+
             offspring_from_crossover: 0.75,
 
+            interspecies_mating_rate: 0.001,
+
+            species_stagnation_generations: 15,
+
+            elitism_count: 1,
+            species_elitism: false,
+            species_elitism_min_size: 5,
+
+            selection: SelectionMethod::Truncation,
+            survival_fraction: 0.1,
+            tournament_size: 3,
+
+            novelty_search: false,
+            novelty_k: 15,
+            novelty_fitness_blend: 1.0,
+            novelty_archive_insertion: ArchiveInsertion::Threshold,
+            novelty_archive_threshold: 1.0,
+            novelty_archive_probability: 0.01,
+            novelty_archive_per_generation: 1,
+            novelty_archive_max_size: 1000,
+
+            phased_search: false,
+            phased_search_complexity_threshold: 30.0,
+            phased_search_stall_generations: 10,
+
             population_size,
             generation_count,
         }
@@ -93,4 +391,317 @@ impl Config {
     pub fn get_generation_count(&self) -> usize {
         self.generation_count
     }
-}
\ No newline at end of file
+
+    /// # from_file
+    /// Reads a config file, see `from_text` for the format
+    pub fn from_file(path: &str) -> io::Result<Config> {
+        Config::from_text(&fs::read_to_string(path)?)
+    }
+
+    /// # from_text
+    /// Parses `key = value` lines, where the keys are the names of the fields.
+    ///
+    /// Empty lines and lines starting with `#` are ignored, missing keys keep their default value.
+    pub fn from_text(text: &str) -> io::Result<Config> {
+        let mut config = Config::new(150, 100);
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |message: String| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", line_number + 1, message),
+                )
+            };
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = parts
+                .next()
+                .ok_or_else(|| invalid(format!("expected 'key = value', got '{}'", line)))?
+                .trim();
+
+            let float = || {
+                value
+                    .parse::<f32>()
+                    .map_err(|_| invalid(format!("'{}' is not a number", value)))
+            };
+            let integer = || {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| invalid(format!("'{}' is not a positive integer", value)))
+            };
+
+            match key {
+                "c1" => config.c1 = float()?,
+                "c2" => config.c2 = float()?,
+                "c3" => config.c3 = float()?,
+                "compatibility_normalisation_threshold" => {
+                    config.compatibility_normalisation_threshold = integer()?
+                }
+                "dt" => config.dt = float()?,
+                "dynamic_dt" => {
+                    config.dynamic_dt = value
+                        .parse::<bool>()
+                        .map_err(|_| invalid(format!("'{}' is not true or false", value)))?
+                }
+                "target_species_count" => config.target_species_count = integer()?,
+                "dt_step" => config.dt_step = float()?,
+                "dt_min" => config.dt_min = float()?,
+                "dt_max" => config.dt_max = float()?,
+                "a_sexual_reproduction_rate" => config.a_sexual_reproduction_rate = float()?,
+                "mutation_rate" => config.mutation_rate = float()?,
+                "pertrubing_rate" => config.pertrubing_rate = float()?,
+                "weight_mutation" => {
+                    config.weight_mutation = WeightMutation::from_name(value)
+                        .ok_or_else(|| invalid(format!("unknown weight mutation '{}'", value)))?
+                }
+                "weight_mutation_gene_rate" => config.weight_mutation_gene_rate = float()?,
+                "weight_perturbation_sigma" => config.weight_perturbation_sigma = float()?,
+                "weight_replace_range" => config.weight_replace_range = float()?,
+                "weight_min" => config.weight_min = float()?,
+                "weight_max" => config.weight_max = float()?,
+                "self_adaptive_mutation" => {
+                    config.self_adaptive_mutation = value
+                        .parse::<bool>()
+                        .map_err(|_| invalid(format!("'{}' is not true or false", value)))?
+                }
+                "mutation_step_min" => config.mutation_step_min = float()?,
+                "mutation_step_max" => config.mutation_step_max = float()?,
+                "disabled_gene_inheriting_chance" => {
+                    config.disabled_gene_inheriting_chance = float()?
+                }
+                "crossover" => {
+                    config.crossover = CrossoverMethod::from_name(value)
+                        .ok_or_else(|| invalid(format!("unknown crossover '{}'", value)))?
+                }
+                "crossover_points" => config.crossover_points = integer()?,
+                "crossover_equal_fitness_inherits_both" => {
+                    config.crossover_equal_fitness_inherits_both = value
+                        .parse::<bool>()
+                        .map_err(|_| invalid(format!("'{}' is not true or false", value)))?
+                }
+                "add_connection_rate" => config.add_connection_rate = float()?,
+                "add_node_rate" => config.add_node_rate = float()?,
+                "toggle_enable_rate" => config.toggle_enable_rate = float()?,
+                "delete_connection_rate" => config.delete_connection_rate = float()?,
+                "delete_node_rate" => config.delete_node_rate = float()?,
+                "offspring_from_crossover" => config.offspring_from_crossover = float()?,
+                "interspecies_mating_rate" => config.interspecies_mating_rate = float()?,
+                "species_stagnation_generations" => {
+                    config.species_stagnation_generations = integer()?
+                }
+                "elitism_count" => config.elitism_count = integer()?,
+                "species_elitism" => {
+                    config.species_elitism = value
+                        .parse::<bool>()
+                        .map_err(|_| invalid(format!("'{}' is not true or false", value)))?
+                }
+                "species_elitism_min_size" => config.species_elitism_min_size = integer()?,
+                "selection" => {
+                    config.selection = SelectionMethod::from_name(value)
+                        .ok_or_else(|| invalid(format!("unknown selection '{}'", value)))?
+                }
+                "survival_fraction" => config.survival_fraction = float()?,
+                "tournament_size" => config.tournament_size = integer()?,
+                "phased_search" => {
+                    config.phased_search = value
+                        .parse::<bool>()
+                        .map_err(|_| invalid(format!("'{}' is not true or false", value)))?
+                }
+                "novelty_search" => {
+                    config.novelty_search = value
+                        .parse::<bool>()
+                        .map_err(|_| invalid(format!("'{}' is not true or false", value)))?
+                }
+                "novelty_k" => config.novelty_k = integer()?,
+                "novelty_fitness_blend" => config.novelty_fitness_blend = float()?,
+                "novelty_archive_insertion" => {
+                    config.novelty_archive_insertion = ArchiveInsertion::from_name(value)
+                        .ok_or_else(|| invalid(format!("unknown archive insertion '{}'", value)))?
+                }
+                "novelty_archive_threshold" => config.novelty_archive_threshold = float()?,
+                "novelty_archive_probability" => config.novelty_archive_probability = float()?,
+                "novelty_archive_per_generation" => {
+                    config.novelty_archive_per_generation = integer()?
+                }
+                "novelty_archive_max_size" => config.novelty_archive_max_size = integer()?,
+                "phased_search_complexity_threshold" => {
+                    config.phased_search_complexity_threshold = float()?
+                }
+                "phased_search_stall_generations" => {
+                    config.phased_search_stall_generations = integer()?
+                }
+                "population_size" => config.population_size = integer()?,
+                "generation_count" => config.generation_count = integer()?,
+                _ => return Err(invalid(format!("unknown key '{}'", key))),
+            }
+        }
+
+        if !config.weight_replace_range.is_finite() || config.weight_replace_range <= 0.0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "weight_replace_range has to be positive and finite",
+            ));
+        }
+        if config.weight_min > config.weight_max {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "weight_min is larger than weight_max",
+            ));
+        }
+        if config.mutation_step_min > config.mutation_step_max {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "mutation_step_min is larger than mutation_step_max",
+            ));
+        }
+        if config.dt_min > config.dt_max {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "dt_min is larger than dt_max",
+            ));
+        }
+        if config.survival_fraction <= 0.0 || config.survival_fraction > 1.0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "survival_fraction must be in (0, 1]",
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// # to_text
+    /// Writes every field as `key = value` lines, readable by `from_text`
+    pub fn to_text(self) -> String {
+        let mut text = String::new();
+
+        text.push_str(&format!("c1 = {}\n", self.c1));
+        text.push_str(&format!("c2 = {}\n", self.c2));
+        text.push_str(&format!("c3 = {}\n", self.c3));
+        text.push_str(&format!(
+            "compatibility_normalisation_threshold = {}\n",
+            self.compatibility_normalisation_threshold
+        ));
+        text.push_str(&format!("dt = {}\n", self.dt));
+        text.push_str(&format!("dynamic_dt = {}\n", self.dynamic_dt));
+        text.push_str(&format!("target_species_count = {}\n", self.target_species_count));
+        text.push_str(&format!("dt_step = {}\n", self.dt_step));
+        text.push_str(&format!("dt_min = {}\n", self.dt_min));
+        text.push_str(&format!("dt_max = {}\n", self.dt_max));
+        text.push_str(&format!(
+            "a_sexual_reproduction_rate = {}\n",
+            self.a_sexual_reproduction_rate
+        ));
+        text.push_str(&format!("mutation_rate = {}\n", self.mutation_rate));
+        text.push_str(&format!("pertrubing_rate = {}\n", self.pertrubing_rate));
+        text.push_str(&format!(
+            "weight_mutation = {}\n",
+            self.weight_mutation.get_name()
+        ));
+        text.push_str(&format!(
+            "weight_mutation_gene_rate = {}\n",
+            self.weight_mutation_gene_rate
+        ));
+        text.push_str(&format!(
+            "weight_perturbation_sigma = {}\n",
+            self.weight_perturbation_sigma
+        ));
+        text.push_str(&format!("weight_replace_range = {}\n", self.weight_replace_range));
+        text.push_str(&format!("weight_min = {}\n", self.weight_min));
+        text.push_str(&format!("weight_max = {}\n", self.weight_max));
+        text.push_str(&format!(
+            "self_adaptive_mutation = {}\n",
+            self.self_adaptive_mutation
+        ));
+        text.push_str(&format!("mutation_step_min = {}\n", self.mutation_step_min));
+        text.push_str(&format!("mutation_step_max = {}\n", self.mutation_step_max));
+        text.push_str(&format!(
+            "disabled_gene_inheriting_chance = {}\n",
+            self.disabled_gene_inheriting_chance
+        ));
+        text.push_str(&format!("crossover = {}\n", self.crossover.get_name()));
+        text.push_str(&format!("crossover_points = {}\n", self.crossover_points));
+        text.push_str(&format!(
+            "crossover_equal_fitness_inherits_both = {}\n",
+            self.crossover_equal_fitness_inherits_both
+        ));
+        text.push_str(&format!("add_connection_rate = {}\n", self.add_connection_rate));
+        text.push_str(&format!("add_node_rate = {}\n", self.add_node_rate));
+        text.push_str(&format!("toggle_enable_rate = {}\n", self.toggle_enable_rate));
+        text.push_str(&format!(
+            "delete_connection_rate = {}\n",
+            self.delete_connection_rate
+        ));
+        text.push_str(&format!("delete_node_rate = {}\n", self.delete_node_rate));
+        text.push_str(&format!(
+            "offspring_from_crossover = {}\n",
+            self.offspring_from_crossover
+        ));
+        text.push_str(&format!(
+            "interspecies_mating_rate = {}\n",
+            self.interspecies_mating_rate
+        ));
+        text.push_str(&format!(
+            "species_stagnation_generations = {}\n",
+            self.species_stagnation_generations
+        ));
+        text.push_str(&format!("elitism_count = {}\n", self.elitism_count));
+        text.push_str(&format!("species_elitism = {}\n", self.species_elitism));
+        text.push_str(&format!(
+            "species_elitism_min_size = {}\n",
+            self.species_elitism_min_size
+        ));
+        text.push_str(&format!("selection = {}\n", self.selection.get_name()));
+        text.push_str(&format!("survival_fraction = {}\n", self.survival_fraction));
+        text.push_str(&format!("tournament_size = {}\n", self.tournament_size));
+        text.push_str(&format!("novelty_search = {}\n", self.novelty_search));
+        text.push_str(&format!("novelty_k = {}\n", self.novelty_k));
+        text.push_str(&format!(
+            "novelty_fitness_blend = {}\n",
+            self.novelty_fitness_blend
+        ));
+        text.push_str(&format!(
+            "novelty_archive_insertion = {}\n",
+            self.novelty_archive_insertion.get_name()
+        ));
+        text.push_str(&format!(
+            "novelty_archive_threshold = {}\n",
+            self.novelty_archive_threshold
+        ));
+        text.push_str(&format!(
+            "novelty_archive_probability = {}\n",
+            self.novelty_archive_probability
+        ));
+        text.push_str(&format!(
+            "novelty_archive_per_generation = {}\n",
+            self.novelty_archive_per_generation
+        ));
+        text.push_str(&format!(
+            "novelty_archive_max_size = {}\n",
+            self.novelty_archive_max_size
+        ));
+        text.push_str(&format!("phased_search = {}\n", self.phased_search));
+        text.push_str(&format!(
+            "phased_search_complexity_threshold = {}\n",
+            self.phased_search_complexity_threshold
+        ));
+        text.push_str(&format!(
+            "phased_search_stall_generations = {}\n",
+            self.phased_search_stall_generations
+        ));
+        text.push_str(&format!("population_size = {}\n", self.population_size));
+        text.push_str(&format!("generation_count = {}\n", self.generation_count));
+
+        text
+    }
+
+    pub fn set_generation_count(&mut self, generation_count: usize) {
+        self.generation_count = generation_count;
+    }
+}
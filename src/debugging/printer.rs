@@ -3,18 +3,352 @@ use crate::NodeGene;
 use crate::NodeGeneType;
 use crate::Genome;
 
-use image::{Rgb, RgbImage};
+use image::gif::Encoder;
+use image::{Delay, Frame, Rgb, RgbImage, Rgba, RgbaImage};
 use imageproc::drawing::*;
 use imageproc::rect::*;
 use rusttype::{Font, Scale};
 
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
 use std::path::Path;
 
-pub struct GenomePrinter {}
+pub struct GenomePrinter {
+    // Recorded genomes of the timeline, together with the caption of each frame
+    timeline: Vec<(Genome, String)>,
+}
 
 impl GenomePrinter {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            timeline: Vec::new(),
+        }
+    }
+
+    /// # record_frame
+    /// Stores a copy of the genome as the next frame of the evolution timeline
+    pub fn record_frame(&mut self, genome: &Genome, caption: &str) {
+        self.timeline.push((genome.clone(), caption.to_owned()));
+    }
+
+    /// # print_timeline
+    /// Renders every recorded frame into a single animated gif at `./output/{path}.gif`.
+    ///
+    /// The layout is computed once from all nodes that appear in any frame,
+    /// so a node keeps its position for the whole animation and new structure is easy to follow.
+    pub fn print_timeline(&mut self, path: &str, frame_delay_ms: u32) {
+        let path = format!("./output/{}.gif", path);
+        let path = Path::new(&path);
+        let width = 800;
+        let height = 800;
+
+        let font = Font::from_bytes(include_bytes!("../SourceSansPro-Bold.ttf") as &[u8]).unwrap();
+        let layout = GenomePrinter::timeline_layout(&self.timeline, width as i32);
+
+        let mut frames: Vec<Frame> = Vec::new();
+        for (genome, caption) in &self.timeline {
+            let image = GenomePrinter::draw_timeline_frame(genome, caption, &layout, &font, width, height);
+            frames.push(Frame::from_parts(
+                image,
+                0,
+                0,
+                Delay::from_numer_denom_ms(frame_delay_ms, 1),
+            ));
+        }
+
+        let file = File::create(path).unwrap();
+        let mut encoder = Encoder::new(file);
+        encoder.encode_frames(frames).unwrap();
+    }
+
+    /// # print_svg
+    /// Writes the svg of `svg_genome` to `./output/{path}.svg`
+    pub fn print_svg(&mut self, genome: &Genome, path: &str) {
+        let path = format!("./output/{}.svg", path);
+        fs::write(path, self.svg_genome(genome, 800, 800)).unwrap();
+    }
+
+    /// # print_dot
+    /// Writes the genome as a graphviz dot graph to `./output/{path}.dot`
+    pub fn print_dot(&mut self, genome: &Genome, path: &str) {
+        let path = format!("./output/{}.dot", path);
+        fs::write(path, self.dot_genome(genome)).unwrap();
+    }
+
+    /// # dot_genome
+    /// The genome as a graphviz dot graph, inputs at the bottom and outputs at the top
+    pub fn dot_genome(&self, genome: &Genome) -> String {
+        let nodes = genome.get_node_genes();
+        let connections = genome.get_connection_genes();
+
+        let mut dot = String::from("digraph genome {\n    rankdir=BT;\n    node [shape=circle, style=filled];\n");
+
+        for (node_type, rank, color) in &[
+            (NodeGeneType::INPUT, "min", "#ffeca9"),
+            (NodeGeneType::OUTPUT, "max", "#ffbbb1"),
+        ] {
+            dot.push_str(&format!("    {{ rank={};", rank));
+            for node in nodes.iter().filter(|x| x.get_type() == *node_type) {
+                dot.push_str(&format!(" {};", node.get_id()));
+            }
+            dot.push_str(" }\n");
+
+            for node in nodes.iter().filter(|x| x.get_type() == *node_type) {
+                dot.push_str(&format!("    {} [fillcolor=\"{}\"];\n", node.get_id(), color));
+            }
+        }
+        for node in nodes.iter().filter(|x| x.get_type() == NodeGeneType::HIDDEN) {
+            dot.push_str(&format!("    {} [fillcolor=\"#c1e4f7\"];\n", node.get_id()));
+        }
+
+        for con in connections {
+            let style = if con.is_expressed() {
+                "solid"
+            } else {
+                "dashed"
+            };
+            dot.push_str(&format!(
+                "    {} -> {} [label=\"{:.3}\", style={}];\n",
+                con.get_in_node(),
+                con.get_out_node(),
+                con.get_weight(),
+                style
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// # svg_genome
+    /// Renders the genome as a standalone svg document.
+    ///
+    /// Nodes are placed in layers by their distance from the inputs, disabled connections are drawn dashed.
+    pub fn svg_genome(&self, genome: &Genome, width: u32, height: u32) -> String {
+        let nodes = genome.get_node_genes();
+        let connections = genome.get_connection_genes();
+
+        // Longest path from any input decides the layer of a node
+        let mut depth: HashMap<i32, usize> = nodes.iter().map(|node| (node.get_id(), 0)).collect();
+        for _ in 0..nodes.len() {
+            let mut changed = false;
+            for con in connections {
+                let in_depth = depth.get(&con.get_in_node()).copied().unwrap_or(0);
+                let out_depth = depth.entry(con.get_out_node()).or_insert(0);
+                if *out_depth < in_depth + 1 {
+                    *out_depth = in_depth + 1;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        // Outputs always share the last layer
+        let last_layer = depth.values().copied().max().unwrap_or(0).max(1);
+        for node in nodes {
+            match node.get_type() {
+                NodeGeneType::INPUT => {
+                    depth.insert(node.get_id(), 0);
+                }
+                NodeGeneType::OUTPUT => {
+                    depth.insert(node.get_id(), last_layer);
+                }
+                NodeGeneType::HIDDEN => {}
+            }
+        }
+
+        let mut layers: Vec<Vec<&NodeGene>> = vec![Vec::new(); last_layer + 1];
+        for node in nodes {
+            let layer = depth[&node.get_id()].min(last_layer);
+            layers[layer].push(node);
+        }
+
+        let mut positions: HashMap<i32, (f32, f32)> = HashMap::new();
+        for (layer_index, layer) in layers.iter().enumerate() {
+            // Inputs at the bottom and outputs at the top, like the png printer
+            let y = height as f32 - (layer_index as f32 + 0.5) * height as f32 / layers.len() as f32;
+            for (i, node) in layer.iter().enumerate() {
+                let x = (i as f32 + 1.0) * width as f32 / (layer.len() as f32 + 1.0);
+                positions.insert(node.get_id(), (x, y));
+            }
+        }
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            width, height, width, height
+        );
+        svg.push_str(&format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+            width, height
+        ));
+
+        for con in connections {
+            let (x1, y1) = positions[&con.get_in_node()];
+            let (x2, y2) = positions[&con.get_out_node()];
+            let (color, dash) = if con.is_expressed() {
+                ("#000000", "")
+            } else {
+                ("#ffb300", " stroke-dasharray=\"6,4\"")
+            };
+
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\"{}><title>{} -&gt; {} ({})</title></line>\n",
+                x1,
+                y1,
+                x2,
+                y2,
+                color,
+                (con.get_weight().abs() * 1.5).clamp(0.5, 6.0),
+                dash,
+                con.get_in_node(),
+                con.get_out_node(),
+                con.get_weight()
+            ));
+        }
+
+        for node in layers.iter().flatten() {
+            let id = node.get_id();
+            let (x, y) = positions[&id];
+            let color = match node.get_type() {
+                NodeGeneType::INPUT => "#ffeca9",
+                NodeGeneType::HIDDEN => "#c1e4f7",
+                NodeGeneType::OUTPUT => "#ffbbb1",
+            };
+
+            svg.push_str(&format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"16\" fill=\"{}\" stroke=\"#000000\" stroke-width=\"2\"/>\n",
+                x, y, color
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"14\" font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x, y, id
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// # timeline_layout
+    /// Assigns a fixed position to every node id found in the timeline.
+    ///
+    /// Inputs are spread along the bottom, outputs along the top,
+    /// and hidden nodes fill a grid in between in the order they were created.
+    fn timeline_layout(timeline: &[(Genome, String)], width: i32) -> HashMap<i32, (i32, i32)> {
+        let mut all_nodes: HashMap<i32, NodeGene> = HashMap::new();
+        for (genome, _) in timeline {
+            for node in genome.get_node_genes() {
+                all_nodes.insert(node.get_id(), *node);
+            }
+        }
+
+        let mut nodes: Vec<NodeGene> = all_nodes.values().copied().collect();
+        nodes.sort_by_key(|node| node.get_id());
+
+        let mut layout: HashMap<i32, (i32, i32)> = HashMap::new();
+
+        // Inputs and outputs are spread evenly on their own row
+        for (node_type, y) in &[(NodeGeneType::INPUT, 680), (NodeGeneType::OUTPUT, 200)] {
+            let row: Vec<&NodeGene> = nodes.iter().filter(|x| x.get_type() == *node_type).collect();
+            let spacing = width / (row.len() as i32 + 1);
+            for (i, node) in row.iter().enumerate() {
+                layout.insert(node.get_id(), (spacing * (i as i32 + 1), *y));
+            }
+        }
+
+        // Hidden nodes fill a grid, every other row is shifted to keep lines from overlapping nodes
+        let hidden: Vec<&NodeGene> = nodes
+            .iter()
+            .filter(|x| x.get_type() == NodeGeneType::HIDDEN)
+            .collect();
+        let columns = (hidden.len() as f32).sqrt().ceil().max(1.0) as i32;
+        let rows = (hidden.len() as i32 + columns - 1) / columns;
+        let spacing_x = width / (columns + 1);
+        let spacing_y = 360 / (rows + 1).max(1);
+        for (i, node) in hidden.iter().enumerate() {
+            let column = i as i32 % columns;
+            let row = i as i32 / columns;
+            let shift = if row % 2 == 1 { spacing_x / 4 } else { 0 };
+
+            layout.insert(
+                node.get_id(),
+                (spacing_x * (column + 1) - shift, 600 - spacing_y * (row + 1)),
+            );
+        }
+
+        layout
+    }
+
+    fn draw_timeline_frame(
+        genome: &Genome,
+        caption: &str,
+        layout: &HashMap<i32, (i32, i32)>,
+        font: &Font,
+        width: u32,
+        height: u32,
+    ) -> RgbaImage {
+        let mut image = RgbaImage::new(width, height);
+
+        let red = Rgba([255u8, 187u8, 177u8, 255u8]);
+        let blue = Rgba([193u8, 228u8, 247u8, 255u8]);
+        let white = Rgba([255u8, 255u8, 255u8, 255u8]);
+        let black = Rgba([0u8, 0u8, 0u8, 255u8]);
+        let yellow = Rgba([255u8, 236u8, 169u8, 255u8]);
+        let expressed_yellow = Rgba([255u8, 179u8, 0u8, 255u8]);
+
+        draw_filled_rect_mut(&mut image, Rect::at(0, 0).of_size(width, height), white);
+
+        let font_scale = Scale { x: 26.0, y: 26.0 };
+        let caption_font_scale = Scale { x: 60.0, y: 60.0 };
+
+        draw_text_mut(&mut image, black, 80, 60, caption_font_scale, font, caption);
+
+        // Lines first so the nodes are drawn on top of them
+        for con in genome.get_connection_genes() {
+            let in_location = layout.get(&con.get_in_node());
+            let out_location = layout.get(&con.get_out_node());
+
+            if let (Some(in_location), Some(out_location)) = (in_location, out_location) {
+                let color = if con.is_expressed() {
+                    black
+                } else {
+                    expressed_yellow
+                };
+
+                draw_line_segment_mut(
+                    &mut image,
+                    (in_location.0 as f32, in_location.1 as f32),
+                    (out_location.0 as f32, out_location.1 as f32),
+                    color,
+                );
+            }
+        }
+
+        for node in genome.get_node_genes() {
+            let (x, y) = layout[&node.get_id()];
+            let color = match node.get_type() {
+                NodeGeneType::INPUT => yellow,
+                NodeGeneType::HIDDEN => blue,
+                NodeGeneType::OUTPUT => red,
+            };
+
+            draw_filled_circle_mut(&mut image, (x, y), 18, black);
+            draw_filled_circle_mut(&mut image, (x, y), 16, color);
+            draw_text_mut(
+                &mut image,
+                black,
+                (x - 5) as u32,
+                (y - 10) as u32,
+                font_scale,
+                font,
+                &node.get_id().to_string(),
+            );
+        }
+
+        image
     }
 
     pub fn print_genome(&mut self, genome: &mut Genome, path: &str, name: &str) {
@@ -59,8 +393,7 @@ impl GenomePrinter {
 
         // Draw inputs
         {
-            let nodes = genome.get_node_genes();
-            let mut nodes: Vec<NodeGene> = nodes.iter().map(|(_, node)| node.clone()).collect();
+            let mut nodes: Vec<NodeGene> = genome.get_node_genes().to_vec();
 
             nodes.sort_by(|a, b| b.get_id().cmp(&a.get_id()));
 
@@ -104,8 +437,7 @@ impl GenomePrinter {
 
         // Draw hidden
         {
-            let nodes = genome.get_node_genes();
-            let mut nodes: Vec<NodeGene> = nodes.iter().map(|(_, node)| node.clone()).collect();
+            let mut nodes: Vec<NodeGene> = genome.get_node_genes().to_vec();
 
             nodes.sort_by(|a, b| b.get_id().cmp(&a.get_id()));
 
@@ -170,8 +502,7 @@ impl GenomePrinter {
 
         // Draw outputs
         {
-            let nodes = genome.get_node_genes();
-            let mut nodes: Vec<NodeGene> = nodes.iter().map(|(_, node)| node.clone()).collect();
+            let mut nodes: Vec<NodeGene> = genome.get_node_genes().to_vec();
 
             nodes.sort_by(|a, b| b.get_id().cmp(&a.get_id()));
 
@@ -225,8 +556,8 @@ impl GenomePrinter {
 
         // Add lines
         let mut current = 0;
-        let connection_count = genome.get_connection_genes().values().count();
-        for con in genome.get_connection_genes().values() {
+        let connection_count = genome.get_connection_genes().len();
+        for con in genome.get_connection_genes() {
             let con: &ConnectionGene = con;
 
             let in_node = con.get_in_node();
@@ -0,0 +1,278 @@
+use crate::Evaluator;
+use crate::GenerationStatistics;
+use crate::GenomePrinter;
+
+use std::fmt;
+use std::fs;
+
+/// # TerminationReason
+/// Why a run stopped, printed at the top of the report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TerminationReason {
+    // All generations of the config were evaluated
+    GenerationLimit,
+    // The fittest genome reached the given fitness
+    FitnessReached(f32),
+    // The run was stopped before either of the above
+    Interrupted,
+}
+
+impl fmt::Display for TerminationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerminationReason::GenerationLimit => write!(f, "Generation limit reached"),
+            TerminationReason::FitnessReached(fitness) => {
+                write!(f, "Fitness target of {} reached", fitness)
+            }
+            TerminationReason::Interrupted => write!(f, "Interrupted"),
+        }
+    }
+}
+
+// A named line of a chart, with its color and how to read its value from the statistics
+type ChartSeries<'a> = (&'a str, &'a str, fn(&GenerationStatistics) -> f32);
+
+#[derive(Default)]
+pub struct ReportPrinter {}
+
+impl ReportPrinter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// # print_report
+    /// Writes a single, self-contained html file to `./output/{path}.html` describing the run of the evaluator.
+    ///
+    /// Charts and the champion are inlined as svg, so the file can be shared on its own.
+    pub fn print_report(&mut self, evaluator: &Evaluator, termination: TerminationReason, path: &str) {
+        let path = format!("./output/{}.html", path);
+        let history = evaluator.get_history();
+        let results = evaluator.get_last_generation_results();
+        let champion = evaluator.get_fittest_genome();
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>NEAT run report</title>\n");
+        html.push_str("<style>\nbody { font-family: sans-serif; margin: 40px; color: #222; }\ntable { border-collapse: collapse; }\ntd, th { border: 1px solid #ccc; padding: 4px 10px; text-align: right; }\nsvg { border: 1px solid #ccc; margin: 10px 0; }\n</style>\n");
+        html.push_str("</head>\n<body>\n<h1>NEAT run report</h1>\n");
+
+        // Summary
+        html.push_str("<h2>Summary</h2>\n<table>\n");
+        html.push_str(&format!("<tr><th>Termination</th><td>{}</td></tr>\n", termination));
+        html.push_str(&format!(
+            "<tr><th>Generations</th><td>{}</td></tr>\n",
+            evaluator.get_generation()
+        ));
+        html.push_str(&format!(
+            "<tr><th>Champion fitness</th><td>{}</td></tr>\n",
+            champion.get_objective_fitness()
+        ));
+        html.push_str("</table>\n");
+
+        // Config
+        html.push_str("<h2>Config</h2>\n<pre>");
+        html.push_str(&ReportPrinter::escape(&format!("{:#?}", evaluator.get_config())));
+        html.push_str("</pre>\n");
+
+        // Charts
+        html.push_str("<h2>Fitness</h2>\n");
+        html.push_str(&ReportPrinter::svg_chart(
+            history,
+            &[
+                ("best", "#d62728", |s: &GenerationStatistics| s.best_fitness),
+                ("mean", "#1f77b4", |s: &GenerationStatistics| s.mean_fitness),
+                ("worst", "#7f7f7f", |s: &GenerationStatistics| s.worst_fitness),
+            ],
+        ));
+
+        html.push_str("<h2>Species</h2>\n");
+        html.push_str(&ReportPrinter::svg_chart(
+            history,
+            &[
+                ("species", "#2ca02c", |s: &GenerationStatistics| {
+                    s.species_count as f32
+                }),
+                ("largest", "#bcbd22", |s: &GenerationStatistics| {
+                    s.largest_species_size as f32
+                }),
+                ("smallest", "#7f7f7f", |s: &GenerationStatistics| {
+                    s.smallest_species_size as f32
+                }),
+            ],
+        ));
+
+        if evaluator.get_config().dynamic_dt {
+            html.push_str("<h2>Compatibility threshold</h2>\n");
+            html.push_str(&ReportPrinter::svg_chart(
+                history,
+                &[("threshold", "#17becf", |s: &GenerationStatistics| {
+                    s.compatibility_threshold
+                })],
+            ));
+        }
+
+        if evaluator.get_config().novelty_search {
+            html.push_str("<h2>Novelty</h2>\n");
+            html.push_str(&ReportPrinter::svg_chart(
+                history,
+                &[("mean novelty", "#e377c2", |s: &GenerationStatistics| {
+                    s.mean_novelty
+                })],
+            ));
+        }
+
+        html.push_str("<h2>Complexity</h2>\n");
+        html.push_str(&ReportPrinter::svg_chart(
+            history,
+            &[
+                ("nodes", "#9467bd", |s: &GenerationStatistics| s.mean_nodes),
+                ("connections", "#ff7f0e", |s: &GenerationStatistics| {
+                    s.mean_connections
+                }),
+            ],
+        ));
+
+        if evaluator.get_config().self_adaptive_mutation {
+            html.push_str("<h2>Mutation step</h2>\n");
+            html.push_str(&ReportPrinter::svg_chart(
+                history,
+                &[("mean step", "#8c564b", |s: &GenerationStatistics| {
+                    s.mean_mutation_step
+                })],
+            ));
+        }
+
+        // Champion
+        html.push_str("<h2>Champion</h2>\n");
+        html.push_str(&GenomePrinter::new().svg_genome(champion.get_genome(), 800, 500));
+
+        // Final species, their members index into the results
+        html.push_str("<h2>Final species</h2>\n<table>\n");
+        html.push_str("<tr><th>Species</th><th>Size</th><th>Champion fitness</th></tr>\n");
+        for (index, species) in evaluator.get_species().iter().enumerate() {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                index + 1,
+                species.get_members().len(),
+                species
+                    .get_champion()
+                    .map_or(0.0, |champion| results[champion].get_objective_fitness())
+            ));
+        }
+        html.push_str("</table>\n");
+
+        // Final population
+        html.push_str("<h2>Final population</h2>\n<table>\n");
+        html.push_str("<tr><th>Rank</th><th>Fitness</th><th>Nodes</th><th>Connections</th><th>Expressed connections</th></tr>\n");
+        for (rank, result) in results.iter().enumerate() {
+            let genome = result.get_genome();
+            let connections = genome.get_connection_genes();
+            let expressed = connections.iter().filter(|c| c.is_expressed()).count();
+
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                rank + 1,
+                result.get_objective_fitness(),
+                genome.get_node_genes().len(),
+                connections.len(),
+                expressed
+            ));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+
+        fs::write(path, html).unwrap();
+    }
+
+    /// # svg_chart
+    /// Draws one line per series over all generations of the history
+    fn svg_chart(
+        history: &[GenerationStatistics],
+        series: &[ChartSeries],
+    ) -> String {
+        let width = 800.0;
+        let height = 300.0;
+        let margin = 50.0;
+
+        let mut min = f32::MAX;
+        let mut max = f32::MIN;
+        for statistics in history {
+            for (_, _, value) in series {
+                min = min.min(value(statistics));
+                max = max.max(value(statistics));
+            }
+        }
+        if history.is_empty() {
+            min = 0.0;
+            max = 1.0;
+        }
+        if (max - min).abs() < f32::EPSILON {
+            max = min + 1.0;
+        }
+
+        let generations = (history.len() as f32 - 1.0).max(1.0);
+        let x_of = |i: usize| margin + (i as f32 / generations) * (width - 2.0 * margin);
+        let y_of = |v: f32| height - margin - ((v - min) / (max - min)) * (height - 2.0 * margin);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+            width, height
+        );
+
+        // Axes and their bounds
+        svg.push_str(&format!(
+            "<line x1=\"{m}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#000\"/>\n<line x1=\"{m}\" y1=\"{m}\" x2=\"{m}\" y2=\"{b}\" stroke=\"#000\"/>\n",
+            m = margin,
+            b = height - margin,
+            r = width - margin
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"end\">{}</text>\n",
+            margin - 5.0,
+            margin,
+            max
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"end\">{}</text>\n",
+            margin - 5.0,
+            height - margin,
+            min
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"end\">generation {}</text>\n",
+            width - margin,
+            height - margin + 20.0,
+            history.last().map_or(0, |statistics| statistics.generation)
+        ));
+
+        for (index, (name, color, value)) in series.iter().enumerate() {
+            let points: Vec<String> = history
+                .iter()
+                .enumerate()
+                .map(|(i, s)| format!("{:.1},{:.1}", x_of(i), y_of(value(s))))
+                .collect();
+
+            svg.push_str(&format!(
+                "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"2\" points=\"{}\"/>\n",
+                color,
+                points.join(" ")
+            ));
+
+            // Legend
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"12\" fill=\"{}\">{}</text>\n",
+                margin + 10.0 + index as f32 * 110.0,
+                margin - 15.0,
+                color,
+                name
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+}
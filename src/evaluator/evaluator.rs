@@ -1,3 +1,4 @@
+use crate::Checkpoint;
 use crate::Config;
 
 use crate::Counter;
@@ -6,19 +7,71 @@ use crate::Genome;
 mod fitness_genome;
 use fitness_genome::FitnessGenome;
 
+pub mod novelty;
+use novelty::NoveltyArchive;
+
+pub mod selection;
+
+pub mod species;
+use species::Species;
+
+pub mod statistics;
+use statistics::GenerationStatistics;
+
 use rand::Rng;
 
+use std::mem;
+
 // https://github.com/hydrozoa-yt/hydroneat/blob/master/src/com/hydrozoa/hydroneat/Evaluator.java
 
+/// # SearchPhase
+/// Phase of Colin Green's phased search, see `Config::phased_search`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchPhase {
+    // Genomes grow through the add node and add connection mutations
+    Complexifying,
+    // Genomes only shrink through the delete mutations, and have their weights mutated
+    Simplifying,
+}
+
+impl SearchPhase {
+    /// # from_name
+    /// Parses the name used in checkpoints
+    pub fn from_name(name: &str) -> Option<SearchPhase> {
+        match name {
+            "complexifying" => Some(SearchPhase::Complexifying),
+            "simplifying" => Some(SearchPhase::Simplifying),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SearchPhase::Complexifying => "complexifying",
+            SearchPhase::Simplifying => "simplifying",
+        }
+    }
+}
+
 pub struct Evaluator {
     config: Config,
 
     next_generation: Vec<Genome>, // stores next generation of genomes (used during evaluation)
     genomes: Vec<Genome>,         // stores all genomes of current generation
-    evaluated_genomes: Vec<FitnessGenome>, // stores all genomes with fitness of current generation (used during evaluation). Incidentally, this list contains results from previous generation.
 
     fittest_genome: FitnessGenome, // Last generation fittest genome
-    last_generation_results: Vec<FitnessGenome>, // Last generations genome fitness-results
+    last_generation_results: Vec<FitnessGenome>, // Last generations genome fitness-results, sorted by fitness. The genomes are moved here when scored
+    species: Vec<Species>, // Species of the last generation, their members index into the results
+    compatibility_threshold: f32, // Threshold the next generation is speciated with, Config::dt unless it is dynamic
+    novelty_archive: NoveltyArchive, // Behaviours of earlier generations, only filled with novelty search
+
+    history: Vec<GenerationStatistics>, // Statistics of every evaluated generation, in order
+    generation: usize,                   // Number of generations evaluated so far
+
+    phase: SearchPhase,              // Phase the next generation is bred in
+    complexity_ceiling: f32,         // Mean number of connections that ends the complexifying phase
+    lowest_complexity: f32,          // Lowest mean number of connections of the simplifying phase
+    generations_since_lowest: usize, // Generations since the simplifying phase reached its lowest complexity
 }
 
 impl Evaluator {
@@ -28,10 +81,20 @@ impl Evaluator {
 
             next_generation: Vec::new(),
             genomes: Vec::new(),
-            evaluated_genomes: Vec::new(),
 
             fittest_genome: FitnessGenome::new(Genome::new(), 0.0),
             last_generation_results: Vec::new(),
+            species: Vec::new(),
+            compatibility_threshold: 0.0,
+            novelty_archive: NoveltyArchive::new(),
+
+            history: Vec::new(),
+            generation: 0,
+
+            phase: SearchPhase::Complexifying,
+            complexity_ceiling: f32::MAX,
+            lowest_complexity: f32::MAX,
+            generations_since_lowest: 0,
         }
     }
 
@@ -44,107 +107,213 @@ impl Evaluator {
         self.config = config.clone();
         self.genomes = Vec::new();
         for _ in 0..self.config.get_population_size() {
-            let g: Genome = genome_provider
+            let mut g: Genome = genome_provider
                 .as_ref()
                 .generate_genesis_genome(&default_genome);
+            // Self-adaptive step sizes start out at the configured sigma
+            g.set_mutation_step(self.config.weight_perturbation_sigma);
             self.genomes.push(g);
         }
         // Reset the values, as this function might be used more than one time
-        self.evaluated_genomes = Vec::new();
         self.next_generation = Vec::new();
 
         self.last_generation_results = Vec::new();
+        self.species = Vec::new();
+        self.compatibility_threshold = self.config.dt;
+        self.novelty_archive = NoveltyArchive::new();
+        self.history = Vec::new();
+        self.generation = 0;
+        self.reset_phase();
+    }
+
+    /// # resume
+    /// Continues a run from a checkpoint, with the population, novelty archive, compatibility threshold
+    /// and phased search state the run had when the checkpoint was saved
+    pub fn resume(&mut self, checkpoint: &Checkpoint) {
+        self.config = checkpoint.config;
+        self.genomes = checkpoint.genomes.clone();
+
+        self.next_generation = Vec::new();
+
+        self.last_generation_results = Vec::new();
+        self.species = Vec::new();
+        self.compatibility_threshold = checkpoint.compatibility_threshold;
+        self.novelty_archive = NoveltyArchive::from_behaviours(checkpoint.novelty_archive.clone());
+        self.history = Vec::new();
+        self.generation = checkpoint.generation;
+
+        self.phase = checkpoint.phase;
+        self.complexity_ceiling = checkpoint.complexity_ceiling;
+        self.lowest_complexity = checkpoint.lowest_complexity;
+        self.generations_since_lowest = checkpoint.generations_since_lowest;
     }
 
-    pub fn evaluate_generation(&mut self, fitness_provider: Box<dyn FitnessGenomeProvider>, mut node_innovation: &mut Counter, mut connection_innovation: &mut Counter) {
+    /// # evaluate_generation
+    /// Scores the genomes and breeds the next generation from them.
+    ///
+    /// Fails, leaving the genomes untouched, when novelty search gets behaviour descriptors it can't compare
+    pub fn evaluate_generation(&mut self, fitness_provider: Box<dyn FitnessGenomeProvider>, mut node_innovation: &mut Counter, mut connection_innovation: &mut Counter) -> Result<(), String> {
         // Reset
         self.last_generation_results.clear();
-        self.evaluated_genomes.clear();
-
-        // Score the genomes
-        for g in &mut self.genomes {
-            let fitness_genome = FitnessGenome::new(
-                g.clone(),
-                fitness_provider.as_ref().fitness_genome_evaluator(&g),
-            );
-            self.evaluated_genomes.push(fitness_genome);
+
+        // Score the genomes, moving them into the results
+        let mut mean_novelty = 0.0;
+        if self.config.novelty_search {
+            let (objectives, behaviours): (Vec<f32>, Vec<Vec<f32>>) = self
+                .genomes
+                .iter()
+                .map(|g| fitness_provider.as_ref().fitness_and_behaviour(g))
+                .unzip();
+            self.novelty_archive.check_behaviours(&behaviours)?;
+            let novelties = self
+                .novelty_archive
+                .novelty_scores(&behaviours, self.config.novelty_k);
+
+            // The genomes are selected by a blend of how novel they are and how well they do the task
+            let blend = self.config.novelty_fitness_blend;
+            for ((g, objective), novelty) in self.genomes.drain(..).zip(objectives).zip(&novelties) {
+                let score = blend * novelty + (1.0 - blend) * objective;
+                self.last_generation_results
+                    .push(FitnessGenome::with_objective(g, score, objective));
+            }
+
+            mean_novelty = novelties.iter().sum::<f32>() / novelties.len().max(1) as f32;
+            self.novelty_archive
+                .insert(&behaviours, &novelties, &self.config);
+        } else {
+            for g in self.genomes.drain(..) {
+                let fitness = fitness_provider.as_ref().fitness_genome_evaluator(&g);
+                self.last_generation_results.push(FitnessGenome::new(g, fitness));
+            }
         }
 
         // Sort evalutated genomes by fitness score
-        self.evaluated_genomes.sort_by(|a, b| {
+        self.last_generation_results.sort_by(|a, b| {
             b.get_fitness()
                 .partial_cmp(&a.get_fitness())
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        // Append genomes to last generation
-        // Needs a temporary clone due to append function clearing the reference
-        let mut temp_evaluated_genomes = self.evaluated_genomes.clone();
-        self.last_generation_results
-            .append(&mut temp_evaluated_genomes);
-
-        // Kill the worst 9 / 10 genomes
-        let cutoff_index = self.evaluated_genomes.len() / 10;
-        let ev_genomes = self.evaluated_genomes.clone();
-        let mut index = 0;
-        for _ in ev_genomes.iter() {
-            if index > cutoff_index {
-                self.evaluated_genomes.remove(index);
-            } else {
-                index += 1;
-            }
+        Species::speciate(
+            &mut self.species,
+            &self.last_generation_results,
+            self.compatibility_threshold,
+            &self.config,
+        );
+
+        // Record the statistics of the generation before it is culled
+        self.generation += 1;
+        let mut statistics = GenerationStatistics::from_results(
+            self.generation,
+            &self.last_generation_results,
+            &self.species,
+        );
+        statistics.compatibility_threshold = self.compatibility_threshold;
+        if self.config.novelty_search {
+            statistics.mean_novelty = mean_novelty;
+            statistics.novelty_archive_size = self.novelty_archive.get_behaviours().len();
+        }
+        if self.config.dynamic_dt {
+            self.update_compatibility_threshold();
         }
+        if self.config.phased_search {
+            self.update_phase(statistics.mean_connections);
+            statistics.complexity_ceiling = Some(self.complexity_ceiling);
+        }
+        statistics.phase = self.phase;
+        self.history.push(statistics);
+        let complexifying = self.phase == SearchPhase::Complexifying;
+        // Phased search only prunes while simplifying, without it the delete mutations always run at their rates
+        let deleting = !self.config.phased_search || !complexifying;
+
+        // Parents are borrowed from the results instead of copied
+        let parents = &self.last_generation_results;
+        let selection = selection::from_config(&self.config);
 
         // Clear the "old" next generation
         self.next_generation.clear();
 
-        // Pick out the most fittest genome
-        let fittest_genome: Genome = self.evaluated_genomes.get(0).unwrap().get_genome();
-
-        self.next_generation.push(fittest_genome);
-        self.fittest_genome = self.evaluated_genomes.get(0).unwrap().clone();
-
-        // Fill the next generation, and mutate it, also add random mating ,)
-        while self.next_generation.len() < self.config.get_population_size() {
-            // Sexual reproduction
-            let mut rng = rand::thread_rng();
-
-            let should_sexually_reproduce: f32 = rng.gen();
-            if should_sexually_reproduce > self.config.a_sexual_reproduction_rate {
-                // Sexual reproduction
-                let parent1 = self
-                    .evaluated_genomes
-                    .get(rng.gen_range(0, self.evaluated_genomes.len()))
-                    .unwrap();
-                let parent2 = self
-                    .evaluated_genomes
-                    .get(rng.gen_range(0, self.evaluated_genomes.len()))
-                    .unwrap();
-
-                // Initialize child
+        // Pick out the most fittest genome, by the task fitness as novelty search doesn't sort by it
+        self.fittest_genome = parents
+            .iter()
+            .min_by(|a, b| {
+                b.get_objective_fitness()
+                    .partial_cmp(&a.get_objective_fitness())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap()
+            .clone();
+
+        // The fittest genomes are copied unchanged
+        let population_size = self.config.get_population_size();
+        let elites = self.config.elitism_count.min(parents.len()).min(population_size);
+        for elite in &parents[..elites] {
+            self.next_generation.push(elite.get_genome().clone());
+        }
+
+        // So are the champions of large enough species, unless they already are one of the elites
+        if self.config.species_elitism {
+            for s in &self.species {
+                let champion = s.get_champion().unwrap();
+                if s.get_members().len() > self.config.species_elitism_min_size
+                    && champion >= elites
+                    && self.next_generation.len() < population_size
+                {
+                    self.next_generation.push(parents[champion].get_genome().clone());
+                }
+            }
+        }
+
+        // Explicit fitness sharing, every species breeds its share of the children from its own members
+        let offspring = Species::offspring_counts(
+            &self.species,
+            parents,
+            population_size - self.next_generation.len(),
+            self.config.species_stagnation_generations,
+        );
+
+        let mut rng = rand::thread_rng();
+        for (own_species, count) in offspring.into_iter().enumerate() {
+            for _ in 0..count {
+                let parent1 =
+                    &parents[self.species[own_species].select_member(selection.as_ref(), parents)];
+
                 let mut child: Genome;
-                // Crossover between parents
-                if parent1.get_fitness() > parent2.get_fitness() {
-                    child = Genome::crossover(
-                        &parent1.get_genome(),
-                        &parent2.get_genome(),
-                        self.config.disabled_gene_inheriting_chance,
+                let should_sexually_reproduce: f32 = rng.gen();
+                if should_sexually_reproduce > self.config.a_sexual_reproduction_rate {
+                    let mate_species = Species::choose_mate_species(
+                        own_species,
+                        self.species.len(),
+                        self.config.interspecies_mating_rate,
                     );
-                } else {
-                    child = Genome::crossover(
-                        &parent2.get_genome(),
-                        &parent1.get_genome(),
-                        self.config.disabled_gene_inheriting_chance,
+                    let parent2 =
+                        &parents[self.species[mate_species].select_member(selection.as_ref(), parents)];
+
+                    // Crossover between parents, the fitter one goes first
+                    let (fitter, other) = if parent1.get_fitness() > parent2.get_fitness() {
+                        (parent1, parent2)
+                    } else {
+                        (parent2, parent1)
+                    };
+                    child = Genome::crossover_with(
+                        fitter.get_genome(),
+                        other.get_genome(),
+                        fitter.get_fitness() == other.get_fitness(),
+                        &self.config,
                     );
-                }
 
-                // Random weights mutation
-                if rng.gen::<f32>() < self.config.mutation_rate {
-                    child.mutation(self.config.pertrubing_rate);
+                    // Random weights mutation
+                    if rng.gen::<f32>() < self.config.mutation_rate {
+                        child.mutation(&self.config);
+                    }
+                } else {
+                    // Mutate the childs weights with the weight mutation of the config
+                    child = parent1.get_genome().clone();
+                    child.mutation(&self.config);
                 }
 
                 // Random add node mutation
-                if rng.gen::<f32>() < self.config.add_node_rate {
+                if complexifying && rng.gen::<f32>() < self.config.add_node_rate {
                     child.add_node_mutation(
                         &mut connection_innovation,
                         &mut node_innovation,
@@ -152,45 +321,156 @@ impl Evaluator {
                 }
 
                 // Random connection mutation
-                if rng.gen::<f32>() < self.config.add_connection_rate {
-                    child.add_connection_mutation(&mut connection_innovation, 100);
+                if complexifying && rng.gen::<f32>() < self.config.add_connection_rate {
+                    child.add_connection_mutation(&mut connection_innovation, 100, &self.config);
                 }
 
-                self.next_generation.push(child);
-            } else {
-                // Get a random parent to base the child from
-                let random_parent_index = rng.gen_range(0, self.evaluated_genomes.len());
-                let parent = self.evaluated_genomes.get(random_parent_index).unwrap();
-                let mut child = parent.get_genome().clone();
+                // Random toggle enable mutation, enabling a connection grows the network so it waits for complexifying
+                if complexifying && rng.gen::<f32>() < self.config.toggle_enable_rate {
+                    child.toggle_enable_mutation();
+                }
+
+                // Random delete connection mutation
+                if deleting && rng.gen::<f32>() < self.config.delete_connection_rate {
+                    child.delete_connection_mutation();
+                }
 
-                // Mutate the childs weights based on the configs pertrubing rate
-                child.mutation(self.config.pertrubing_rate);
+                // Random delete node mutation
+                if deleting && rng.gen::<f32>() < self.config.delete_node_rate {
+                    child.delete_node_mutation();
+                }
 
                 self.next_generation.push(child);
             }
         }
 
         // Transfer next generation to next current generation
-        self.genomes.clear();
-        for g in &self.next_generation {
-            self.genomes.push(g.clone());
+        self.genomes = mem::take(&mut self.next_generation);
+
+        Ok(())
+    }
+
+    /// # update_phase
+    /// Switches between complexifying and simplifying based on the mean number of connections of the last generation,
+    /// `evaluate_generation` calls it every generation when `Config::phased_search` is on
+    pub fn update_phase(&mut self, complexity: f32) {
+        match self.phase {
+            SearchPhase::Complexifying => {
+                // The first ceiling is relative to the starting complexity
+                if self.complexity_ceiling == f32::MAX {
+                    self.complexity_ceiling = complexity + self.config.phased_search_complexity_threshold;
+                }
+
+                if complexity > self.complexity_ceiling {
+                    self.phase = SearchPhase::Simplifying;
+                    self.lowest_complexity = complexity;
+                    self.generations_since_lowest = 0;
+                }
+            }
+            SearchPhase::Simplifying => {
+                if complexity < self.lowest_complexity {
+                    self.lowest_complexity = complexity;
+                    self.generations_since_lowest = 0;
+                } else {
+                    self.generations_since_lowest += 1;
+                }
+
+                // Complexity stopped falling, grow again from where the pruning ended
+                if self.generations_since_lowest >= self.config.phased_search_stall_generations {
+                    self.phase = SearchPhase::Complexifying;
+                    self.complexity_ceiling = complexity + self.config.phased_search_complexity_threshold;
+                }
+            }
+        }
+    }
+
+    /// # update_compatibility_threshold
+    /// Steps the threshold towards `Config::target_species_count`, more species need a larger threshold
+    fn update_compatibility_threshold(&mut self) {
+        let species_count = self.species.len();
+        if species_count > self.config.target_species_count {
+            self.compatibility_threshold += self.config.dt_step;
+        } else if species_count < self.config.target_species_count {
+            self.compatibility_threshold -= self.config.dt_step;
         }
+
+        self.compatibility_threshold = self
+            .compatibility_threshold
+            .max(self.config.dt_min)
+            .min(self.config.dt_max);
+    }
+
+    fn reset_phase(&mut self) {
+        self.phase = SearchPhase::Complexifying;
+        self.complexity_ceiling = f32::MAX;
+        self.lowest_complexity = f32::MAX;
+        self.generations_since_lowest = 0;
+    }
+
+    pub fn get_phase(&self) -> SearchPhase {
+        self.phase
     }
 
-    pub fn get_fittest_genome(&self) -> FitnessGenome {
-        self.fittest_genome.clone()
+    /// # get_complexity_ceiling
+    /// Mean number of connections that ends the complexifying phase, `f32::MAX` until the first phase update
+    pub fn get_complexity_ceiling(&self) -> f32 {
+        self.complexity_ceiling
+    }
+
+    /// # get_lowest_complexity
+    /// Lowest mean number of connections of the current simplifying phase
+    pub fn get_lowest_complexity(&self) -> f32 {
+        self.lowest_complexity
+    }
+
+    /// # get_generations_since_lowest
+    /// Generations since the simplifying phase reached its lowest complexity
+    pub fn get_generations_since_lowest(&self) -> usize {
+        self.generations_since_lowest
+    }
+
+    /// # get_species
+    /// Species of the last evaluated generation, their members are indices into `get_last_generation_results`
+    pub fn get_species(&self) -> &[Species] {
+        &self.species
+    }
+
+    /// # get_compatibility_threshold
+    /// Threshold the next generation is speciated with
+    pub fn get_compatibility_threshold(&self) -> f32 {
+        self.compatibility_threshold
+    }
+
+    pub fn get_novelty_archive(&self) -> &NoveltyArchive {
+        &self.novelty_archive
+    }
+
+    pub fn get_fittest_genome(&self) -> &FitnessGenome {
+        &self.fittest_genome
     }
 
     pub fn get_genome_amount(&self) -> usize {
         self.genomes.len()
     }
 
-    pub fn get_genomes(&self) -> Vec<Genome> {
-        self.genomes.clone()
+    pub fn get_genomes(&self) -> &[Genome] {
+        &self.genomes
+    }
+
+    pub fn get_last_generation_results(&self) -> &[FitnessGenome] {
+        &self.last_generation_results
     }
 
-    pub fn get_last_generation_results(&self) -> Vec<FitnessGenome> {
-        self.last_generation_results.clone()
+    pub fn get_history(&self) -> &[GenerationStatistics] {
+        &self.history
+    }
+
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn get_config(&self) -> Config {
+        self.config
     }
 }
 
@@ -200,4 +480,18 @@ pub trait GenesisGenomeProvider {
 
 pub trait FitnessGenomeProvider {
     fn fitness_genome_evaluator(&self, genome: &Genome) -> f32;
+
+    /// # fitness_and_behaviour
+    /// The fitness along with a behaviour descriptor, used by novelty search to measure how novel a genome is.
+    ///
+    /// Every genome of a task should have a descriptor of the same length, the default has an empty one
+    fn fitness_and_behaviour(&self, genome: &Genome) -> (f32, Vec<f32>) {
+        (self.fitness_genome_evaluator(genome), Vec::new())
+    }
+
+    /// # has_behaviour
+    /// Whether `fitness_and_behaviour` gives real descriptors, so the task can be used with novelty search
+    fn has_behaviour(&self) -> bool {
+        false
+    }
 }
@@ -2,20 +2,39 @@ use crate::Genome;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FitnessGenome {
-    fitness: f32,
+    fitness: f32,   // Score the genome is selected by
+    objective: f32, // Fitness returned by the task, differs from the score with novelty search
     genome: Genome,
 }
 
 impl FitnessGenome {
     pub fn new(genome: Genome, fitness: f32) -> FitnessGenome {
-        FitnessGenome { fitness, genome }
+        FitnessGenome {
+            fitness,
+            objective: fitness,
+            genome,
+        }
+    }
+
+    /// # with_objective
+    /// A genome selected by `fitness` that scored `objective` on the task
+    pub fn with_objective(genome: Genome, fitness: f32, objective: f32) -> FitnessGenome {
+        FitnessGenome {
+            fitness,
+            objective,
+            genome,
+        }
     }
 
     pub fn get_fitness(&self) -> f32 {
         self.fitness
     }
 
-    pub fn get_genome(&self) -> Genome {
-        self.genome.clone()
+    pub fn get_objective_fitness(&self) -> f32 {
+        self.objective
+    }
+
+    pub fn get_genome(&self) -> &Genome {
+        &self.genome
     }
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use crate::Config;
+
+use rand::Rng;
+
+/// # ArchiveInsertion
+/// Which behaviours of a generation `NoveltyArchive::insert` keeps, chosen through `Config::novelty_archive_insertion`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveInsertion {
+    // Every behaviour more novel than `Config::novelty_archive_threshold`
+    Threshold,
+    // Every behaviour with a chance of `Config::novelty_archive_probability`
+    Random,
+    // The `Config::novelty_archive_per_generation` most novel behaviours
+    MostNovel,
+}
+
+impl ArchiveInsertion {
+    /// # from_name
+    /// Parses the name used in config files
+    pub fn from_name(name: &str) -> Option<ArchiveInsertion> {
+        match name {
+            "threshold" => Some(ArchiveInsertion::Threshold),
+            "random" => Some(ArchiveInsertion::Random),
+            "most-novel" => Some(ArchiveInsertion::MostNovel),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ArchiveInsertion::Threshold => "threshold",
+            ArchiveInsertion::Random => "random",
+            ArchiveInsertion::MostNovel => "most-novel",
+        }
+    }
+}
+
+/// # NoveltyArchive
+/// Behaviours of earlier generations, so novelty search keeps rewarding behaviours that haven't been seen in a while.
+///
+/// [Abandoning Objectives: Evolution through the Search for Novelty Alone](http://eplex.cs.ucf.edu/papers/lehman_ecj11.pdf)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoveltyArchive {
+    behaviours: Vec<Vec<f32>>, // Archived behaviour descriptors, oldest first
+}
+
+impl NoveltyArchive {
+    pub fn new() -> NoveltyArchive {
+        NoveltyArchive {
+            behaviours: Vec::new(),
+        }
+    }
+
+    /// # from_behaviours
+    /// An archive holding earlier behaviours, oldest first, e.g. those of a checkpoint
+    pub fn from_behaviours(behaviours: Vec<Vec<f32>>) -> NoveltyArchive {
+        NoveltyArchive { behaviours }
+    }
+
+    pub fn get_behaviours(&self) -> &[Vec<f32>] {
+        &self.behaviours
+    }
+
+    /// # check_behaviours
+    /// Whether the behaviours can be compared with each other and the archive: none may be empty,
+    /// and all must have the same length as the archived ones
+    pub fn check_behaviours(&self, behaviours: &[Vec<f32>]) -> Result<(), String> {
+        let expected = match self.behaviours.first().or_else(|| behaviours.first()) {
+            Some(behaviour) => behaviour.len(),
+            None => return Ok(()),
+        };
+
+        if expected == 0 || behaviours.iter().any(|behaviour| behaviour.is_empty()) {
+            return Err(String::from(
+                "empty behaviour descriptor, novelty search needs FitnessGenomeProvider::fitness_and_behaviour",
+            ));
+        }
+        if let Some(behaviour) = behaviours.iter().find(|behaviour| behaviour.len() != expected) {
+            return Err(format!(
+                "behaviour descriptor of length {} where {} was expected",
+                behaviour.len(),
+                expected
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// # novelty_scores
+    /// The mean distance of every behaviour to its `k` nearest neighbours among the other behaviours and the archive
+    pub fn novelty_scores(&self, behaviours: &[Vec<f32>], k: usize) -> Vec<f32> {
+        behaviours
+            .iter()
+            .enumerate()
+            .map(|(index, behaviour)| {
+                let mut distances: Vec<f32> = behaviours
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(_, other)| other)
+                    .chain(self.behaviours.iter())
+                    .map(|other| behaviour_distance(behaviour, other))
+                    .collect();
+                if distances.is_empty() {
+                    return 0.0;
+                }
+
+                let neighbours = k.max(1).min(distances.len());
+                distances.select_nth_unstable_by(neighbours - 1, |a, b| {
+                    a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+                distances[..neighbours].iter().sum::<f32>() / neighbours as f32
+            })
+            .collect()
+    }
+
+    /// # insert
+    /// Archives the behaviours picked by `Config::novelty_archive_insertion`, dropping the oldest ones past the maximum size
+    pub fn insert(&mut self, behaviours: &[Vec<f32>], novelties: &[f32], config: &Config) {
+        let mut rng = rand::thread_rng();
+
+        match config.novelty_archive_insertion {
+            ArchiveInsertion::Threshold => {
+                for (behaviour, novelty) in behaviours.iter().zip(novelties) {
+                    if *novelty > config.novelty_archive_threshold {
+                        self.behaviours.push(behaviour.clone());
+                    }
+                }
+            }
+            ArchiveInsertion::Random => {
+                for behaviour in behaviours {
+                    if rng.gen::<f32>() < config.novelty_archive_probability {
+                        self.behaviours.push(behaviour.clone());
+                    }
+                }
+            }
+            ArchiveInsertion::MostNovel => {
+                let mut order: Vec<usize> = (0..behaviours.len()).collect();
+                order.sort_by(|a, b| {
+                    novelties[*b]
+                        .partial_cmp(&novelties[*a])
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                for index in order.into_iter().take(config.novelty_archive_per_generation) {
+                    self.behaviours.push(behaviours[index].clone());
+                }
+            }
+        }
+
+        if self.behaviours.len() > config.novelty_archive_max_size {
+            let excess = self.behaviours.len() - config.novelty_archive_max_size;
+            self.behaviours.drain(..excess);
+        }
+    }
+}
+
+/// # behaviour_distance
+/// Euclidean distance between two behaviour descriptors of the same length, see `NoveltyArchive::check_behaviours`
+pub fn behaviour_distance(behaviour1: &[f32], behaviour2: &[f32]) -> f32 {
+    behaviour1
+        .iter()
+        .zip(behaviour2)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
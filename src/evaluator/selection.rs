@@ -0,0 +1,234 @@
+use crate::Config;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// # Selection
+/// Picks the parents of the next generation.
+///
+/// `fitnesses` holds the fitness of every genome of the generation, sorted from fittest to least fit,
+/// and the returned values are indices into it. A genome can be picked any number of times.
+pub trait Selection {
+    fn select(&self, fitnesses: &[f32], count: usize) -> Vec<usize>;
+}
+
+/// # SelectionMethod
+/// The built-in selection strategies, chosen through `Config::selection`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionMethod {
+    // Uniformly among the best `Config::survival_fraction` of the generation
+    Truncation,
+    // The fittest of `Config::tournament_size` uniformly drawn genomes
+    Tournament,
+    // Fitness-proportionate
+    Roulette,
+    // Fitness-proportionate with evenly spaced pointers, less noisy than roulette
+    StochasticUniversalSampling,
+    // Linear ranking, the chance of a genome only depends on its position
+    Rank,
+}
+
+impl SelectionMethod {
+    /// # from_name
+    /// Parses the name used in config files
+    pub fn from_name(name: &str) -> Option<SelectionMethod> {
+        match name {
+            "truncation" => Some(SelectionMethod::Truncation),
+            "tournament" => Some(SelectionMethod::Tournament),
+            "roulette" => Some(SelectionMethod::Roulette),
+            "sus" => Some(SelectionMethod::StochasticUniversalSampling),
+            "rank" => Some(SelectionMethod::Rank),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SelectionMethod::Truncation => "truncation",
+            SelectionMethod::Tournament => "tournament",
+            SelectionMethod::Roulette => "roulette",
+            SelectionMethod::StochasticUniversalSampling => "sus",
+            SelectionMethod::Rank => "rank",
+        }
+    }
+}
+
+/// # from_config
+/// The selection strategy of the config, with its parameters
+pub fn from_config(config: &Config) -> Box<dyn Selection> {
+    match config.selection {
+        SelectionMethod::Truncation => Box::new(Truncation::new(config.survival_fraction)),
+        SelectionMethod::Tournament => Box::new(Tournament::new(config.tournament_size)),
+        SelectionMethod::Roulette => Box::new(Roulette {}),
+        SelectionMethod::StochasticUniversalSampling => Box::new(StochasticUniversalSampling {}),
+        SelectionMethod::Rank => Box::new(Rank {}),
+    }
+}
+
+pub struct Truncation {
+    survival_fraction: f32,
+}
+
+impl Truncation {
+    pub fn new(survival_fraction: f32) -> Self {
+        Self { survival_fraction }
+    }
+}
+
+impl Selection for Truncation {
+    fn select(&self, fitnesses: &[f32], count: usize) -> Vec<usize> {
+        if fitnesses.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rng = rand::thread_rng();
+        let survivors = ((fitnesses.len() as f32 * self.survival_fraction).ceil() as usize)
+            .clamp(1, fitnesses.len());
+
+        (0..count).map(|_| rng.gen_range(0, survivors)).collect()
+    }
+}
+
+pub struct Tournament {
+    size: usize,
+}
+
+impl Tournament {
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+}
+
+impl Selection for Tournament {
+    fn select(&self, fitnesses: &[f32], count: usize) -> Vec<usize> {
+        if fitnesses.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rng = rand::thread_rng();
+
+        // The fitnesses are sorted, so the fittest contestant is the one with the lowest index
+        (0..count)
+            .map(|_| {
+                (0..self.size.max(1))
+                    .map(|_| rng.gen_range(0, fitnesses.len()))
+                    .min()
+                    .unwrap()
+            })
+            .collect()
+    }
+}
+
+/// # proportional_weights
+/// The fitnesses as weights, negative fitnesses are shifted up until the least fit genome has a weight of zero
+fn proportional_weights(fitnesses: &[f32]) -> Vec<f32> {
+    let lowest = fitnesses.iter().copied().fold(f32::MAX, f32::min).min(0.0);
+
+    fitnesses.iter().map(|fitness| fitness - lowest).collect()
+}
+
+/// # uniform_picks
+/// `count` uniformly drawn indices, the fallback when the weights can't be spun, e.g. all zero or not finite
+fn uniform_picks(len: usize, count: usize) -> Vec<usize> {
+    let mut rng = rand::thread_rng();
+
+    (0..count).map(|_| rng.gen_range(0, len)).collect()
+}
+
+/// # pick_with_pointers
+/// Walks the cumulative weights once, picking the genome each sorted pointer falls on
+fn pick_with_pointers(weights: &[f32], pointers: &[f32]) -> Vec<usize> {
+    let mut picked: Vec<usize> = Vec::with_capacity(pointers.len());
+
+    let mut index = 0;
+    let mut cumulative = weights[0];
+    for pointer in pointers {
+        while *pointer >= cumulative && index < weights.len() - 1 {
+            index += 1;
+            cumulative += weights[index];
+        }
+        picked.push(index);
+    }
+
+    picked
+}
+
+pub struct Roulette {}
+
+impl Selection for Roulette {
+    fn select(&self, fitnesses: &[f32], count: usize) -> Vec<usize> {
+        if fitnesses.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rng = rand::thread_rng();
+        let weights = proportional_weights(fitnesses);
+        let total: f32 = weights.iter().sum();
+        if !(total.is_finite() && total > 0.0) {
+            return uniform_picks(fitnesses.len(), count);
+        }
+
+        let mut pointers: Vec<f32> = (0..count).map(|_| rng.gen_range(0.0, total)).collect();
+        pointers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mut picked = pick_with_pointers(&weights, &pointers);
+        picked.shuffle(&mut rng);
+        picked
+    }
+}
+
+pub struct StochasticUniversalSampling {}
+
+impl Selection for StochasticUniversalSampling {
+    fn select(&self, fitnesses: &[f32], count: usize) -> Vec<usize> {
+        if fitnesses.is_empty() || count == 0 {
+            return Vec::new();
+        }
+
+        let mut rng = rand::thread_rng();
+        let weights = proportional_weights(fitnesses);
+        let total: f32 = weights.iter().sum();
+        // A tiny total spread over many pointers can leave no distance between them
+        let distance = total / count as f32;
+        if !(total.is_finite() && distance > 0.0) {
+            return uniform_picks(fitnesses.len(), count);
+        }
+
+        // One spin places every pointer
+        let start = rng.gen_range(0.0, distance);
+        let pointers: Vec<f32> = (0..count).map(|i| start + i as f32 * distance).collect();
+
+        // The pointers pick the genomes in order, shuffle them so parents are paired at random
+        let mut picked = pick_with_pointers(&weights, &pointers);
+        picked.shuffle(&mut rng);
+        picked
+    }
+}
+
+pub struct Rank {}
+
+impl Selection for Rank {
+    fn select(&self, fitnesses: &[f32], count: usize) -> Vec<usize> {
+        if fitnesses.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rng = rand::thread_rng();
+
+        // The fittest genome has a weight of n, the least fit a weight of 1
+        let weights: Vec<f32> = (0..fitnesses.len())
+            .map(|rank| (fitnesses.len() - rank) as f32)
+            .collect();
+        let total: f32 = weights.iter().sum();
+        if !(total.is_finite() && total > 0.0) {
+            return uniform_picks(fitnesses.len(), count);
+        }
+
+        let mut pointers: Vec<f32> = (0..count).map(|_| rng.gen_range(0.0, total)).collect();
+        pointers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mut picked = pick_with_pointers(&weights, &pointers);
+        picked.shuffle(&mut rng);
+        picked
+    }
+}
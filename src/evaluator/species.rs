@@ -0,0 +1,198 @@
+use super::fitness_genome::FitnessGenome;
+use super::selection::Selection;
+use crate::Config;
+use crate::Genome;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// # Species
+/// A niche of genomes within the compatibility threshold of its mascot.
+///
+/// The mascot is a random member of the previous generation, so species keep their identity across generations.
+pub struct Species {
+    mascot: Genome,      // Genome new members are compared to
+    members: Vec<usize>, // Indices into the generation results, sorted from fittest to least fit
+    best_fitness: f32,   // Highest fitness any member ever had
+    stagnation: usize,   // Generations since best_fitness last improved
+}
+
+impl Species {
+    pub fn new(mascot: Genome) -> Species {
+        Species {
+            mascot,
+            members: Vec::new(),
+            best_fitness: f32::MIN,
+            stagnation: 0,
+        }
+    }
+
+    /// # get_stagnation
+    /// Number of generations since the species last improved on its best fitness
+    pub fn get_stagnation(&self) -> usize {
+        self.stagnation
+    }
+
+    pub fn get_mascot(&self) -> &Genome {
+        &self.mascot
+    }
+
+    pub fn get_members(&self) -> &[usize] {
+        &self.members
+    }
+
+    /// # get_champion
+    /// Index of the fittest member
+    pub fn get_champion(&self) -> Option<usize> {
+        self.members.first().copied()
+    }
+
+    /// # select_member
+    /// Picks a member with the selection strategy, competing only with the other members of the species
+    pub fn select_member(&self, selection: &dyn Selection, results: &[FitnessGenome]) -> usize {
+        let fitnesses: Vec<f32> = self
+            .members
+            .iter()
+            .map(|member| results[*member].get_fitness())
+            .collect();
+
+        self.members[selection.select(&fitnesses, 1)[0]]
+    }
+
+    /// # offspring_counts
+    /// Splits `total` children between the species in proportion to the mean fitness of their members.
+    ///
+    /// This is NEAT's explicit fitness sharing, a new structure only competes within its own species,
+    /// so it gets a few generations to optimise its weights. Negative fitnesses are shifted up to zero first.
+    ///
+    /// Species that haven't improved for `stagnation_limit` generations get no children, the elites still
+    /// carry over their best genomes. A limit of 0 never stops a species
+    pub fn offspring_counts(
+        species: &[Species],
+        results: &[FitnessGenome],
+        total: usize,
+        stagnation_limit: usize,
+    ) -> Vec<usize> {
+        if species.is_empty() {
+            return Vec::new();
+        }
+
+        let lowest = results
+            .iter()
+            .map(|result| result.get_fitness())
+            .fold(f32::MAX, f32::min)
+            .min(0.0);
+        let mut shares: Vec<f32> = species
+            .iter()
+            .map(|s| {
+                if stagnation_limit > 0 && s.stagnation >= stagnation_limit {
+                    return 0.0;
+                }
+
+                s.members
+                    .iter()
+                    .map(|member| results[*member].get_fitness() - lowest)
+                    .sum::<f32>()
+                    / s.members.len() as f32
+            })
+            .collect();
+        let total_share: f32 = shares.iter().sum();
+        if total_share <= 0.0 || !total_share.is_finite() {
+            shares = vec![1.0; species.len()];
+        }
+        let total_share: f32 = shares.iter().sum();
+
+        // Largest remainder, so the counts always add up to the total
+        let exact: Vec<f32> = shares
+            .iter()
+            .map(|share| share / total_share * total as f32)
+            .collect();
+        let mut counts: Vec<usize> = exact.iter().map(|e| e.floor() as usize).collect();
+        let mut order: Vec<usize> = (0..species.len()).collect();
+        order.sort_by(|a, b| {
+            (exact[*b] - exact[*b].floor())
+                .partial_cmp(&(exact[*a] - exact[*a].floor()))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let missing = total - counts.iter().sum::<usize>();
+        for index in order.into_iter().cycle().take(missing) {
+            counts[index] += 1;
+        }
+
+        counts
+    }
+
+    /// # choose_mate_species
+    /// Index of the species a member of `own_species` mates with, with a chance of `interspecies_mating_rate`
+    /// it is any of the other species, otherwise its own
+    pub fn choose_mate_species(
+        own_species: usize,
+        species_count: usize,
+        interspecies_mating_rate: f32,
+    ) -> usize {
+        let mut rng = rand::thread_rng();
+
+        if species_count < 2 || rng.gen::<f32>() >= interspecies_mating_rate {
+            return own_species;
+        }
+
+        // Skip over the own species, so every other species is equally likely
+        let other = rng.gen_range(0, species_count - 1);
+        if other >= own_species {
+            other + 1
+        } else {
+            other
+        }
+    }
+
+    /// # is_compatible
+    /// Whether the genome is closer to the mascot than the compatibility threshold `dt`
+    pub fn is_compatible(&self, genome: &Genome, dt: f32, config: &Config) -> bool {
+        Genome::compatibility_distance(
+            genome,
+            &self.mascot,
+            config.c1,
+            config.c2,
+            config.c3,
+            config.compatibility_normalisation_threshold,
+        ) < dt
+    }
+
+    /// # speciate
+    /// Puts every result, sorted by fitness, in the first compatible species, or founds a new one.
+    ///
+    /// Species left without members go extinct, the survivors pick a new mascot for the next generation.
+    pub fn speciate(species: &mut Vec<Species>, results: &[FitnessGenome], dt: f32, config: &Config) {
+        for s in species.iter_mut() {
+            s.members.clear();
+        }
+
+        for (index, result) in results.iter().enumerate() {
+            let genome = result.get_genome();
+            match species.iter_mut().find(|s| s.is_compatible(genome, dt, config)) {
+                Some(s) => s.members.push(index),
+                None => {
+                    let mut founded = Species::new(genome.clone());
+                    founded.members.push(index);
+                    species.push(founded);
+                }
+            }
+        }
+
+        species.retain(|s| !s.members.is_empty());
+
+        let mut rng = rand::thread_rng();
+        for s in species.iter_mut() {
+            let best = results[s.members[0]].get_fitness();
+            if best > s.best_fitness {
+                s.best_fitness = best;
+                s.stagnation = 0;
+            } else {
+                s.stagnation += 1;
+            }
+
+            let mascot = *s.members.choose(&mut rng).unwrap();
+            s.mascot = results[mascot].get_genome().clone();
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::fitness_genome::FitnessGenome;
+use super::species::Species;
+use super::SearchPhase;
+
+/// # GenerationStatistics
+/// Summary of one evaluated generation, recorded by the `Evaluator` for every call to `evaluate_generation`.
+///
+/// The fitnesses are those returned by the task, also with novelty search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenerationStatistics {
+    pub generation: usize,
+
+    pub best_fitness: f32,
+    pub mean_fitness: f32,
+    pub worst_fitness: f32,
+
+    // Number of species the population was divided into, and the members of the largest and smallest one
+    pub species_count: usize,
+    pub largest_species_size: usize,
+    pub smallest_species_size: usize,
+    // Compatibility threshold the population was divided with
+    pub compatibility_threshold: f32,
+
+    pub mean_nodes: f32,
+    pub mean_connections: f32,
+    // Mean self-adaptive mutation step size of the genomes
+    pub mean_mutation_step: f32,
+
+    // Mean novelty of the genomes and size of the novelty archive, zero without novelty search
+    pub mean_novelty: f32,
+    pub novelty_archive_size: usize,
+
+    // Phase the next generation is bred in
+    pub phase: SearchPhase,
+    // Mean number of connections that starts the simplifying phase, None without phased search
+    pub complexity_ceiling: Option<f32>,
+}
+
+impl GenerationStatistics {
+    pub fn from_results(
+        generation: usize,
+        results: &[FitnessGenome],
+        species: &[Species],
+    ) -> GenerationStatistics {
+        let count = results.len().max(1) as f32;
+
+        let mut best_fitness = f32::MIN;
+        let mut worst_fitness = f32::MAX;
+        let mut total_fitness = 0.0;
+        let mut total_nodes = 0;
+        let mut total_connections = 0;
+        let mut total_mutation_step = 0.0;
+
+        for result in results {
+            best_fitness = best_fitness.max(result.get_objective_fitness());
+            worst_fitness = worst_fitness.min(result.get_objective_fitness());
+            total_fitness += result.get_objective_fitness();
+
+            let genome = result.get_genome();
+            total_nodes += genome.get_node_genes().len();
+            total_connections += genome.get_connection_genes().len();
+            total_mutation_step += genome.get_mutation_step();
+        }
+
+        if results.is_empty() {
+            best_fitness = 0.0;
+            worst_fitness = 0.0;
+        }
+
+        GenerationStatistics {
+            generation,
+
+            best_fitness,
+            mean_fitness: total_fitness / count,
+            worst_fitness,
+
+            species_count: species.len(),
+            largest_species_size: species.iter().map(|s| s.get_members().len()).max().unwrap_or(0),
+            smallest_species_size: species.iter().map(|s| s.get_members().len()).min().unwrap_or(0),
+            compatibility_threshold: 0.0,
+
+            mean_nodes: total_nodes as f32 / count,
+            mean_connections: total_connections as f32 / count,
+            mean_mutation_step: total_mutation_step / count,
+
+            // Set by the evaluator with novelty search
+            mean_novelty: 0.0,
+            novelty_archive_size: 0,
+
+            // Set by the evaluator once the phase of the next generation is known
+            phase: SearchPhase::Complexifying,
+            complexity_ceiling: None,
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::fs;
+use std::io;
+
+use crate::CompiledNetwork;
+use crate::Genome;
+
+/// # Language
+/// The languages a genome can be exported to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Rust,
+    C,
+}
+
+impl Language {
+    /// # get_extension
+    /// File extension of source files in the language
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            Language::Rust => "rs",
+            Language::C => "c",
+        }
+    }
+}
+
+// A node of the exported function, every line computes one of them
+struct ExportedNode {
+    index: usize,
+    // (source index, weight) in the order the phenotype adds them up
+    terms: Vec<(usize, f32)>,
+}
+
+/// # exported_nodes
+/// The computed nodes of the compiled network with their incoming connections
+fn exported_nodes(network: &CompiledNetwork) -> Vec<ExportedNode> {
+    let mut nodes: Vec<ExportedNode> = (network.get_input_count()..network.get_computed_count())
+        .map(|index| ExportedNode {
+            index,
+            terms: Vec::new(),
+        })
+        .collect();
+
+    for ((source, target), weight) in network
+        .get_sources()
+        .iter()
+        .zip(network.get_targets())
+        .zip(network.get_weights())
+    {
+        nodes[target - network.get_input_count()]
+            .terms
+            .push((*source, *weight));
+    }
+
+    nodes
+}
+
+/// # identifier
+/// Turns a name into a valid function name for both languages
+fn identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        identifier.insert(0, '_');
+    }
+
+    identifier
+}
+
+/// # weighted_sum
+/// The sum of the terms, written left to right so it is rounded exactly like the phenotype
+fn weighted_sum(terms: &[(usize, f32)], literal: fn(f32) -> String) -> String {
+    let mut sum = literal(0.0);
+    for (source, weight) in terms {
+        sum.push_str(&format!(" + v[{}] * {}", source, literal(*weight)));
+    }
+
+    sum
+}
+
+/// # rust_literal
+/// The value as an `f32` literal, `{:?}` of a non-finite value is not valid Rust so those use the constants
+fn rust_literal(value: f32) -> String {
+    if value.is_nan() {
+        String::from("f32::NAN")
+    } else if value == f32::INFINITY {
+        String::from("f32::INFINITY")
+    } else if value == f32::NEG_INFINITY {
+        String::from("f32::NEG_INFINITY")
+    } else {
+        format!("{:?}_f32", value)
+    }
+}
+
+/// # c_literal
+/// The value as a `float` literal, non-finite values use the `math.h` macros
+fn c_literal(value: f32) -> String {
+    if value.is_nan() {
+        String::from("NAN")
+    } else if value == f32::INFINITY {
+        String::from("INFINITY")
+    } else if value == f32::NEG_INFINITY {
+        String::from("-INFINITY")
+    } else {
+        format!("{:?}f", value)
+    }
+}
+
+/// # genome_to_rust
+/// A dependency-free Rust function `fn <name>(inputs: &[f32; N]) -> [f32; M]` evaluating the phenotype of the genome.
+///
+/// The weights are inlined as constants and the outputs are identical to `CompiledNetwork::activate`.
+pub fn genome_to_rust(genome: &Genome, name: &str) -> String {
+    let network = CompiledNetwork::from_genome(genome);
+    let input_count = network.get_input_count();
+    let output_count = network.get_output_count();
+
+    let mut code = String::new();
+    code.push_str(&format!(
+        "/// Evolved network with {} inputs and {} outputs, generated by neat-java\n",
+        input_count, output_count
+    ));
+    code.push_str(&format!(
+        "pub fn {}(inputs: &[f32; {}]) -> [f32; {}] {{\n",
+        identifier(name),
+        input_count,
+        output_count
+    ));
+    code.push_str("    fn sigmoid(x: f32) -> f32 {\n        1.0 / (1.0 + (-4.9 * x).exp())\n    }\n\n");
+    code.push_str(&format!(
+        "    let mut v = [0.0_f32; {}];\n",
+        network.get_node_count()
+    ));
+    code.push_str(&format!(
+        "    v[..{}].copy_from_slice(inputs);\n",
+        input_count
+    ));
+
+    for node in exported_nodes(&network) {
+        code.push_str(&format!(
+            "    v[{}] = sigmoid({});\n",
+            node.index,
+            weighted_sum(&node.terms, rust_literal)
+        ));
+    }
+
+    let outputs: Vec<String> = network
+        .get_output_indices()
+        .iter()
+        .map(|index| format!("v[{}]", index))
+        .collect();
+    code.push_str(&format!("\n    [{}]\n}}\n", outputs.join(", ")));
+
+    code
+}
+
+/// # genome_to_c
+/// A C99 function `void <name>(const float inputs[N], float outputs[M])` evaluating the phenotype of the genome.
+///
+/// Only needs `math.h`. The outputs are identical to `CompiledNetwork::activate` as long as
+/// the compiler does not contract or reorder floating point math (no `-ffast-math`, `-ffp-contract=off`).
+pub fn genome_to_c(genome: &Genome, name: &str) -> String {
+    let network = CompiledNetwork::from_genome(genome);
+    let input_count = network.get_input_count();
+    let output_count = network.get_output_count();
+    let name = identifier(name);
+
+    let mut code = String::new();
+    code.push_str(&format!(
+        "/* Evolved network with {} inputs and {} outputs, generated by neat-java */\n",
+        input_count, output_count
+    ));
+    code.push_str("#include <math.h>\n\n");
+    code.push_str(&format!(
+        "static float {}_sigmoid(float x) {{\n    return 1.0f / (1.0f + expf(-4.9f * x));\n}}\n\n",
+        name
+    ));
+    code.push_str(&format!(
+        "void {}(const float inputs[{}], float outputs[{}]) {{\n",
+        name,
+        input_count.max(1),
+        output_count.max(1)
+    ));
+    code.push_str(&format!(
+        "    float v[{}] = {{0}};\n",
+        network.get_node_count().max(1)
+    ));
+    code.push_str(&format!(
+        "    for (int i = 0; i < {}; i++) {{\n        v[i] = inputs[i];\n    }}\n",
+        input_count
+    ));
+
+    for node in exported_nodes(&network) {
+        code.push_str(&format!(
+            "    v[{}] = {}_sigmoid({});\n",
+            node.index,
+            name,
+            weighted_sum(&node.terms, c_literal)
+        ));
+    }
+
+    code.push('\n');
+    for (output, index) in network.get_output_indices().iter().enumerate() {
+        code.push_str(&format!("    outputs[{}] = v[{}];\n", output, index));
+    }
+    code.push_str("}\n");
+
+    code
+}
+
+/// # save_source
+/// Writes the genome as a `language` source file, with the function named `name`
+pub fn save_source(genome: &Genome, name: &str, language: Language, path: &str) -> io::Result<()> {
+    let code = match language {
+        Language::Rust => genome_to_rust(genome, name),
+        Language::C => genome_to_c(genome, name),
+    };
+
+    fs::write(path, code)
+}
@@ -44,6 +44,10 @@ impl ConnectionGene {
         self.expressed = false;
     }
 
+    pub fn enable(&mut self) {
+        self.expressed = true;
+    }
+
     pub fn get_innovation(&self) -> i32 {
         self.innovation.to_owned()
     }
@@ -9,6 +9,26 @@ impl Counter {
         }
     }
 
+    /// # starting_at
+    /// A counter that continues after an already handed out innovation number
+    pub fn starting_at(current_innovation: i32) -> Self {
+        Self { current_innovation }
+    }
+
+    /// # get_current_innovation
+    /// The last handed out innovation number, without advancing the counter
+    pub fn get_current_innovation(&self) -> i32 {
+        self.current_innovation
+    }
+
+    /// # advance_to
+    /// Makes sure the counter never hands out `innovation` or anything below it again
+    pub fn advance_to(&mut self, innovation: i32) {
+        if self.current_innovation < innovation {
+            self.current_innovation = innovation;
+        }
+    }
+
     pub fn get_innovation(&mut self) -> i32 {
         self.current_innovation += 1;
 
@@ -1,6 +1,4 @@
 use std::cmp;
-use std::collections::hash_map::Keys;
-use std::collections::HashMap;
 
 use rand::Rng;
 use rand_distr::{Distribution, Normal};
@@ -13,72 +11,410 @@ use connection_gene::ConnectionGene;
 use node_gene::NodeGene;
 use node_gene::NodeGeneType;
 
+use crate::Config;
 use crate::Counter;
+use crate::CrossoverMethod;
+use crate::WeightMutation;
+
+/// # StartingTopology
+/// How the inputs and outputs of a minimal genome are connected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StartingTopology {
+    // Every input connected to every output
+    FullyConnected,
+    // The given fraction of all input-output pairs, picked at random
+    Sparse(f32),
+    // No connections at all
+    Unconnected,
+    // A single random input-output connection, as in FS-NEAT
+    FsNeat,
+}
+
+/// # GeneComparison
+/// How the connection genes of two genomes line up, see `Genome::compare_genes`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneComparison {
+    pub matching: usize,
+    pub disjoint: usize,
+    pub excess: usize,
+    // Mean absolute weight difference of the matching genes, zero without matching genes
+    pub mean_weight_difference: f32,
+}
+
+/// Mutation step size of a new genome, the same as the default `Config::weight_perturbation_sigma`
+pub const DEFAULT_MUTATION_STEP: f32 = 0.5;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Genome {
-    connections: HashMap<i32, ConnectionGene>,
-    nodes: HashMap<i32, NodeGene>,
+    // Connections sorted by innovation number, nodes sorted by id
+    connections: Vec<ConnectionGene>,
+    nodes: Vec<NodeGene>,
+
+    // Standard deviation of the weight perturbations when self-adaptive mutation is on, evolves with the genome
+    mutation_step: f32,
 }
 
 impl Genome {
     pub fn new() -> Self {
         Self {
-            connections: HashMap::new(),
-            nodes: HashMap::new(),
+            connections: Vec::new(),
+            nodes: Vec::new(),
+
+            mutation_step: DEFAULT_MUTATION_STEP,
+        }
+    }
+
+    /// # minimal
+    /// A genome with `input_count` inputs and `output_count` outputs, connected according to `topology`.
+    ///
+    /// The numbering only depends on the input and output counts: inputs are nodes `1..=input_count`,
+    /// outputs follow them, and the connection from input `i` to output `o` (both zero based) always gets
+    /// innovation `o * input_count + i + 1`. Genomes built this way line up in `crossover` whatever
+    /// subset of the pairs they hold. The counters are advanced past every number this reserves.
+    pub fn minimal(
+        input_count: usize,
+        output_count: usize,
+        topology: StartingTopology,
+        node_innovation: &mut Counter,
+        connection_innovation: &mut Counter,
+    ) -> Genome {
+        let mut rng = rand::thread_rng();
+        let mut genome = Genome::new();
+
+        for i in 0..input_count {
+            genome.add_node_gene(NodeGene::new(NodeGeneType::INPUT, i as i32 + 1));
         }
+        for o in 0..output_count {
+            genome.add_node_gene(NodeGene::new(
+                NodeGeneType::OUTPUT,
+                (input_count + o) as i32 + 1,
+            ));
+        }
+
+        // Pick which input-output pairs are connected, as indices into every possible pair
+        let pair_count = input_count * output_count;
+        let pairs: Vec<usize> = match topology {
+            StartingTopology::FullyConnected => (0..pair_count).collect(),
+            StartingTopology::Sparse(fraction) => {
+                let amount = (pair_count as f32 * fraction).round() as usize;
+                let amount = if fraction > 0.0 { amount.max(1) } else { 0 };
+                let mut pairs =
+                    rand::seq::index::sample(&mut rng, pair_count, amount.min(pair_count)).into_vec();
+                pairs.sort_unstable();
+                pairs
+            }
+            StartingTopology::Unconnected => Vec::new(),
+            StartingTopology::FsNeat if pair_count > 0 => vec![rng.gen_range(0, pair_count)],
+            StartingTopology::FsNeat => Vec::new(),
+        };
+
+        for pair in pairs {
+            let input = pair % input_count;
+            let output = pair / input_count;
+
+            genome.add_connection_gene(ConnectionGene::new(
+                input as i32 + 1,
+                (input_count + output) as i32 + 1,
+                rng.gen_range(-1.0, 1.0),
+                true,
+                pair as i32 + 1,
+            ));
+        }
+
+        node_innovation.advance_to((input_count + output_count) as i32);
+        connection_innovation.advance_to(pair_count as i32);
+
+        genome
+    }
+
+    /// # fully_connected
+    /// A minimal genome with every input connected to every output
+    pub fn fully_connected(
+        input_count: usize,
+        output_count: usize,
+        node_innovation: &mut Counter,
+        connection_innovation: &mut Counter,
+    ) -> Genome {
+        Genome::minimal(
+            input_count,
+            output_count,
+            StartingTopology::FullyConnected,
+            node_innovation,
+            connection_innovation,
+        )
+    }
+
+    /// # sparsely_connected
+    /// A minimal genome with `fraction` of the input-output pairs connected
+    pub fn sparsely_connected(
+        input_count: usize,
+        output_count: usize,
+        fraction: f32,
+        node_innovation: &mut Counter,
+        connection_innovation: &mut Counter,
+    ) -> Genome {
+        Genome::minimal(
+            input_count,
+            output_count,
+            StartingTopology::Sparse(fraction),
+            node_innovation,
+            connection_innovation,
+        )
+    }
+
+    /// # unconnected
+    /// A minimal genome with inputs and outputs but no connections
+    pub fn unconnected(
+        input_count: usize,
+        output_count: usize,
+        node_innovation: &mut Counter,
+        connection_innovation: &mut Counter,
+    ) -> Genome {
+        Genome::minimal(
+            input_count,
+            output_count,
+            StartingTopology::Unconnected,
+            node_innovation,
+            connection_innovation,
+        )
+    }
+
+    /// # fs_neat
+    /// A minimal genome with a single random input-output connection
+    pub fn fs_neat(
+        input_count: usize,
+        output_count: usize,
+        node_innovation: &mut Counter,
+        connection_innovation: &mut Counter,
+    ) -> Genome {
+        Genome::minimal(
+            input_count,
+            output_count,
+            StartingTopology::FsNeat,
+            node_innovation,
+            connection_innovation,
+        )
+    }
+
+    /// # with_hidden_layer
+    /// A genome with one layer of `hidden_count` hidden nodes, every input connected to every hidden node
+    /// and every hidden node connected to every output.
+    ///
+    /// Inputs and outputs are numbered as in `minimal`, the hidden nodes follow the outputs.
+    /// The innovations of `minimal` are reserved, then input `i` to hidden `h` gets
+    /// `input_count * output_count + h * input_count + i + 1` and hidden `h` to output `o` continues after those
+    /// with `o * hidden_count + h`. Genomes with the same counts always line up in `crossover`.
+    pub fn with_hidden_layer(
+        input_count: usize,
+        output_count: usize,
+        hidden_count: usize,
+        node_innovation: &mut Counter,
+        connection_innovation: &mut Counter,
+    ) -> Genome {
+        let mut rng = rand::thread_rng();
+        let mut genome = Genome::minimal(
+            input_count,
+            output_count,
+            StartingTopology::Unconnected,
+            node_innovation,
+            connection_innovation,
+        );
+
+        let first_hidden = input_count + output_count + 1;
+        for h in 0..hidden_count {
+            genome.add_node_gene(NodeGene::new(NodeGeneType::HIDDEN, (first_hidden + h) as i32));
+        }
+
+        let first_input_to_hidden = input_count * output_count + 1;
+        for h in 0..hidden_count {
+            for i in 0..input_count {
+                genome.add_connection_gene(ConnectionGene::new(
+                    i as i32 + 1,
+                    (first_hidden + h) as i32,
+                    rng.gen_range(-1.0, 1.0),
+                    true,
+                    (first_input_to_hidden + h * input_count + i) as i32,
+                ));
+            }
+        }
+
+        let first_hidden_to_output = first_input_to_hidden + input_count * hidden_count;
+        for o in 0..output_count {
+            for h in 0..hidden_count {
+                genome.add_connection_gene(ConnectionGene::new(
+                    (first_hidden + h) as i32,
+                    (input_count + o) as i32 + 1,
+                    rng.gen_range(-1.0, 1.0),
+                    true,
+                    (first_hidden_to_output + o * hidden_count + h) as i32,
+                ));
+            }
+        }
+
+        node_innovation.advance_to((first_hidden + hidden_count) as i32 - 1);
+        connection_innovation.advance_to((first_hidden_to_output + output_count * hidden_count) as i32 - 1);
+
+        genome
     }
 
     /// # get_connection_genes
-    /// Get Genome's connection genes
-    pub fn get_connection_genes(&self) -> HashMap<i32, ConnectionGene> {
-        self.connections.to_owned()
+    /// Get Genome's connection genes, sorted by innovation number
+    pub fn get_connection_genes(&self) -> &[ConnectionGene] {
+        &self.connections
+    }
+
+    /// # get_connection_genes_mut
+    /// Mutable access to Genome's connection genes, innovation numbers can't be changed so the order is kept
+    pub fn get_connection_genes_mut(&mut self) -> &mut [ConnectionGene] {
+        &mut self.connections
+    }
+
+    /// # get_connection_gene
+    /// Finds the connection gene with the given innovation number
+    pub fn get_connection_gene(&self, innovation: i32) -> Option<&ConnectionGene> {
+        self.connections
+            .binary_search_by_key(&innovation, |con| con.get_innovation())
+            .ok()
+            .map(|index| &self.connections[index])
+    }
+
+    /// # get_connection_gene_mut
+    /// Finds the connection gene with the given innovation number, for changing its weight or expression
+    pub fn get_connection_gene_mut(&mut self, innovation: i32) -> Option<&mut ConnectionGene> {
+        match self
+            .connections
+            .binary_search_by_key(&innovation, |con| con.get_innovation())
+        {
+            Ok(index) => Some(&mut self.connections[index]),
+            Err(_) => None,
+        }
     }
 
     /// # get_node_genes
-    /// Get Genome's node genes
-    pub fn get_node_genes(&self) -> HashMap<i32, NodeGene> {
-        self.nodes.to_owned()
+    /// Get Genome's node genes, sorted by id
+    pub fn get_node_genes(&self) -> &[NodeGene] {
+        &self.nodes
+    }
+
+    /// # get_node_gene
+    /// Finds the node gene with the given id
+    pub fn get_node_gene(&self, id: i32) -> Option<&NodeGene> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.get_id())
+            .ok()
+            .map(|index| &self.nodes[index])
+    }
+
+    pub fn get_mutation_step(&self) -> f32 {
+        self.mutation_step
+    }
+
+    pub fn set_mutation_step(&mut self, mutation_step: f32) {
+        self.mutation_step = mutation_step;
     }
 
     /// # mutation
-    /// Mutates the weights
-    pub fn mutation(&mut self, probability_perturbing: f32) {
+    /// Mutates the weights with the operator selected by `config.weight_mutation`,
+    /// the new weights are clamped to `config.weight_min` and `config.weight_max`.
+    ///
+    /// With `config.self_adaptive_mutation` the genome first mutates its own step size,
+    /// which then replaces `config.weight_perturbation_sigma` and the fixed scale of the multiplicative operator
+    pub fn mutation(&mut self, config: &Config) {
         let mut rng = rand::thread_rng();
 
-        for con in self.connections.values_mut() {
-            let rnd_float: f32 = rng.gen_range(0.0, 1.0);
+        let mut sigma = config.weight_perturbation_sigma;
+        if config.self_adaptive_mutation {
+            // Log-normal update with the learning rate of evolution strategies, 1 / sqrt(number of weights)
+            let tau = 1.0 / (self.connections.len().max(1) as f32).sqrt();
+            let step = self.mutation_step * (tau * Genome::gaussian(1.0)).exp();
+            self.mutation_step = step.clamp(config.mutation_step_min, config.mutation_step_max);
+            sigma = self.mutation_step;
+        }
 
-            // Uniformly perturbing weights
-            if rnd_float < probability_perturbing {
-                let normal = Normal::new(0.0, 1.0).unwrap();
-                let v = normal.sample(&mut rand::thread_rng());
+        match config.weight_mutation {
+            WeightMutation::OneGene => {
+                if self.connections.is_empty() {
+                    return;
+                }
 
-                // Nudge the weight a random amount of a normal distribution with peak=0.0 and deviation=1
-                con.set_weight(con.get_weight() * v);
-            } else {
-                // Assign a weight between -2.0 and 2.0
-                let rnd_float: f32 = rng.gen_range(-2.0, 2.0);
-                con.set_weight(rnd_float);
+                // A single gene takes the whole perturbation, its size grows with the genome
+                // so the change is as large as perturbing every gene
+                let sigma = sigma * (self.connections.len() as f32).sqrt();
+                let index = rng.gen_range(0, self.connections.len());
+                let con = &mut self.connections[index];
+                let weight = con.get_weight() + Genome::gaussian(sigma);
+                con.set_weight(weight.clamp(config.weight_min, config.weight_max));
+            }
+            WeightMutation::Gaussian | WeightMutation::Multiplicative => {
+                for con in self.connections.iter_mut() {
+                    if rng.gen::<f32>() >= config.weight_mutation_gene_rate {
+                        continue;
+                    }
+
+                    let weight = if rng.gen::<f32>() < config.pertrubing_rate {
+                        if config.weight_mutation == WeightMutation::Multiplicative {
+                            if config.self_adaptive_mutation {
+                                // Scale the weight by 1 + a sample of N(0, sigma), so the genome's step size sets how far it moves
+                                con.get_weight() * (1.0 + Genome::gaussian(sigma))
+                            } else {
+                                // Scale the weight by a sample of a normal distribution with peak=0.0 and deviation=1
+                                con.get_weight() * Genome::gaussian(1.0)
+                            }
+                        } else {
+                            // Nudge the weight by a sample of a normal distribution with peak=0.0 and deviation=sigma
+                            con.get_weight() + Genome::gaussian(sigma)
+                        }
+                    } else {
+                        // Assign a new weight within the replace range
+                        rng.gen_range(-config.weight_replace_range, config.weight_replace_range)
+                    };
+
+                    con.set_weight(weight.clamp(config.weight_min, config.weight_max));
+                }
             }
         }
     }
 
+    /// # gaussian
+    /// A sample of a normal distribution with peak=0.0 and deviation=sigma
+    fn gaussian(sigma: f32) -> f32 {
+        let normal: Normal<f32> = Normal::new(0.0, 1.0).unwrap();
+        normal.sample(&mut rand::thread_rng()) * sigma
+    }
+
     /// # add_node_gene
-    /// Adds a new node gene to the Genome
+    /// Adds a new node gene to the Genome, replacing any node with the same id
     pub fn add_node_gene(&mut self, gene: NodeGene) {
-        self.nodes.insert(gene.get_id(), gene);
+        match self
+            .nodes
+            .binary_search_by_key(&gene.get_id(), |node| node.get_id())
+        {
+            Ok(index) => self.nodes[index] = gene,
+            Err(index) => self.nodes.insert(index, gene),
+        }
     }
 
     /// # add_connection_gene
-    /// Adds a new connection gene to the Genome
+    /// Adds a new connection gene to the Genome, replacing any connection with the same innovation number
     pub fn add_connection_gene(&mut self, gene: ConnectionGene) {
-        self.connections.insert(gene.get_innovation(), gene);
+        match self
+            .connections
+            .binary_search_by_key(&gene.get_innovation(), |con| con.get_innovation())
+        {
+            Ok(index) => self.connections[index] = gene,
+            Err(index) => self.connections.insert(index, gene),
+        }
     }
 
     /// # add_connection_mutation
-    /// Mutates connections for genome to the Genome
-    pub fn add_connection_mutation(&mut self, innovation: &mut Counter, max_attempts: i32) {
+    /// Mutates connections for genome to the Genome,
+    /// the new weight is clamped to `config.weight_min` and `config.weight_max`
+    pub fn add_connection_mutation(
+        &mut self,
+        innovation: &mut Counter,
+        max_attempts: i32,
+        config: &Config,
+    ) {
         let mut tries: i32 = 0;
         let mut success: bool = false;
 
@@ -87,13 +423,11 @@ impl Genome {
         while tries < max_attempts && !success {
             tries += 1;
 
-            let node_inovation_numbers: Vec<i32> = Genome::keys_to_vec(self.nodes.keys());
-            let key_node1 = node_inovation_numbers[rng.gen_range(0, node_inovation_numbers.len())];
-            let key_node2 = node_inovation_numbers[rng.gen_range(0, node_inovation_numbers.len())];
-
-            let mut node1 = self.nodes.get(&key_node1).unwrap();
-            let mut node2 = self.nodes.get(&key_node2).unwrap();
-            let weight: f32 = rng.gen_range(-1.0, 1.0);
+            let mut node1 = &self.nodes[rng.gen_range(0, self.nodes.len())];
+            let mut node2 = &self.nodes[rng.gen_range(0, self.nodes.len())];
+            let weight: f32 = rng
+                .gen_range(-1.0f32, 1.0)
+                .clamp(config.weight_min, config.weight_max);
 
             // Check if the nodes should be reversed, in case, do it
             let reversed = if node1.get_type() == NodeGeneType::HIDDEN
@@ -139,9 +473,7 @@ impl Genome {
             // List of nodes that requires output from node2
             let mut node_ids: Vec<i32> = Vec::new();
             // Add nodes that need checking and nodes that requires output from node2
-            for conn_id in self.connections.keys() {
-                let gene = self.connections.get(conn_id).unwrap();
-
+            for gene in &self.connections {
                 if gene.get_in_node() == node2.get_id() {
                     // connection comes from node2
                     node_ids.push(gene.get_out_node());
@@ -151,9 +483,7 @@ impl Genome {
 
             while !needs_checking.is_empty() {
                 let node_id = needs_checking.get(0).unwrap().to_owned();
-                for conn_id in self.connections.keys() {
-                    let gene = self.connections.get(conn_id).unwrap();
-
+                for gene in &self.connections {
                     // connection comes from the needs_checking node
                     if gene.get_in_node() == node_id {
                         node_ids.push(gene.get_out_node());
@@ -172,7 +502,7 @@ impl Genome {
             }
 
             let mut connection_exists: bool = false;
-            for con in self.connections.values() {
+            for con in &self.connections {
                 // Existing connection
                 if con.get_in_node() == node1.get_id() && con.get_out_node() == node2.get_id() {
                     connection_exists = true;
@@ -197,7 +527,7 @@ impl Genome {
                 true,
                 innovation.get_innovation(),
             );
-            self.connections.insert(new_con.get_innovation(), new_con);
+            self.add_connection_gene(new_con);
 
             success = true;
         }
@@ -218,7 +548,7 @@ impl Genome {
 
         // Find some suitable connections in the genome
         let mut suitable_connections: Vec<i32> = Vec::new();
-        for connection in self.connections.values() {
+        for connection in &self.connections {
             if connection.is_expressed() {
                 suitable_connections.push(connection.get_innovation());
             }
@@ -231,15 +561,16 @@ impl Genome {
         }
 
         let length = rng.gen_range(0, suitable_connections.len());
-        let con = suitable_connections.get(length).unwrap();
-        let con = self.connections.get_mut(con).unwrap();
-
-        // Get the connections in and out nodes, then disable the connection and create a new one
-        let in_node = self.nodes.get(&(con.get_in_node() as i32)).unwrap().clone();
-        let out_node = self.nodes.get(&(con.get_out_node() as i32)).unwrap().clone();
+        let con = suitable_connections[length];
+        let con = self.get_connection_gene_mut(con).unwrap();
 
         // Disable the connection
         con.disable();
+        let con = *con;
+
+        // Get the connections in and out nodes, then create the new ones
+        let in_node = *self.get_node_gene(con.get_in_node()).unwrap();
+        let out_node = *self.get_node_gene(con.get_out_node()).unwrap();
 
         // Add the new node and the new connections
         let new_node = NodeGene::new(NodeGeneType::HIDDEN, node_innovation.get_innovation());
@@ -260,373 +591,428 @@ impl Genome {
             connection_innovation.get_innovation(),
         );
 
-        self.nodes.insert(new_node.get_id(), new_node);
+        self.add_node_gene(new_node);
 
-        self.connections
-            .insert(in_to_new.get_innovation(), in_to_new);
-        self.connections
-            .insert(new_to_out.get_innovation(), new_to_out);
+        self.add_connection_gene(in_to_new);
+        self.add_connection_gene(new_to_out);
     }
 
-    /// # crossover
-    /// ### Takes two parents and outputs a child.
+    /// # toggle_enable_mutation
+    /// Flips whether a random connection is expressed.
     ///
-    /// **parent_1** is always the more "fit" parent
-    ///
-    /// **parent_2** is the less "fit" parent
-    pub fn crossover(
-        parent_1: &Genome,
-        parent_2: &Genome,
-        disabled_gene_inheriting_chance: f32,
-    ) -> Genome {
-        let mut child = Genome::new();
+    /// The phenotype is feed-forward, so a disabled connection is only picked when enabling it doesn't close a cycle.
+    /// Returns whether a connection could be toggled.
+    pub fn toggle_enable_mutation(&mut self) -> bool {
+        let mut rng = rand::thread_rng();
+
+        let suitable_connections: Vec<i32> = self
+            .connections
+            .iter()
+            .filter(|con| con.is_expressed() || !self.creates_cycle(con.get_in_node(), con.get_out_node()))
+            .map(|con| con.get_innovation())
+            .collect();
 
-        // Add nodes to the child from the most fit parent, in this case it's parent 1
-        for parent_1_node in parent_1.get_node_genes().values() {
-            child.add_node_gene(parent_1_node.clone());
+        if suitable_connections.is_empty() {
+            return false;
         }
 
-        // Add connection genes to the child
-        for parent_1_con in parent_1.get_connection_genes().values() {
-            let mut rng = rand::thread_rng();
+        let innovation = suitable_connections[rng.gen_range(0, suitable_connections.len())];
+        let con = self.get_connection_gene_mut(innovation).unwrap();
+        if con.is_expressed() {
+            con.disable();
+        } else {
+            con.enable();
+        }
 
-            // Find a matching gene or add the most fittest one
-            if parent_2
-                .get_connection_genes()
-                .contains_key(&parent_1_con.get_innovation())
-            {
-                // Get parent 2 connection
-                let parent_2_con = parent_2
-                    .get_connection_genes()
-                    .get(&parent_1_con.get_innovation())
-                    .unwrap()
-                    .clone();
-
-                // Check if both are disabled
-                let disabled: bool = !parent_1_con.is_expressed() || !parent_2_con.is_expressed();
-
-                // Child connection gene
-                let mut child_con_gene: ConnectionGene = if rng.gen() {
-                    parent_1_con.clone()
-                } else {
-                    parent_2_con.clone()
-                };
-
-                // Give it a random chance to disable
-                let rnd_float: f32 = rng.gen();
-                if disabled && rnd_float < disabled_gene_inheriting_chance {
-                    child_con_gene.disable();
-                }
+        true
+    }
 
-                // Add a random gene from one of the parents to the child
-                child.add_connection_gene(child_con_gene);
-            } else {
-                let connection_gene = parent_1_con.clone();
+    /// # creates_cycle
+    /// Whether an expressed connection from `in_node` to `out_node` would close a cycle of expressed connections
+    pub fn creates_cycle(&self, in_node: i32, out_node: i32) -> bool {
+        let mut visited: Vec<i32> = vec![out_node];
+        let mut needs_checking: Vec<i32> = vec![out_node];
 
-                // If it's disjoint or excess, add the most fit one to the child
-                child.add_connection_gene(connection_gene);
+        while let Some(node_id) = needs_checking.pop() {
+            if node_id == in_node {
+                return true;
+            }
+
+            for con in &self.connections {
+                if con.is_expressed()
+                    && con.get_in_node() == node_id
+                    && !visited.contains(&con.get_out_node())
+                {
+                    visited.push(con.get_out_node());
+                    needs_checking.push(con.get_out_node());
+                }
             }
         }
 
-        child
+        false
     }
 
-    /// # compatibility_distance
-    /// How identical two genomes are, identical ones will output a zero,
-    /// [article describing the equation.](http://nn.cs.utexas.edu/downloads/papers/stanley.ec02.pdf)
+    /// # delete_connection_mutation
+    /// Removes a random connection, hidden nodes left without any connection are removed with it.
     ///
-    /// C1, C2, and C3 are importance coefficients'
-    pub fn compatibility_distance(
-        genome1: &Genome,
-        genome2: &Genome,
-        c1: f32,
-        c2: f32,
-        c3: f32,
-    ) -> f32 {
-        let excess_genes = Genome::count_excess_genes(&genome1, &genome2) as f32;
-        let disjoint_genes = Genome::count_disjoint_genes(&genome1, &genome2) as f32;
-        let avg_weight_diff = Genome::average_weight_diff(&genome1, &genome2);
-
-        // Number of genes in the larger genome, normalizes for genome size
-        // (can be set to 1 if both genomes are small, i.e, consists of fewer than 20 genes)
-        let n = 1.0;
-
-        ((c1 * excess_genes) / n) + ((c2 * disjoint_genes) / n) + c3 * avg_weight_diff
-    }
+    /// A connection is kept when it is the last expressed one of an input or output node, so they never end up cut off.
+    /// Returns whether a connection could be deleted.
+    pub fn delete_connection_mutation(&mut self) -> bool {
+        let mut rng = rand::thread_rng();
 
-    pub fn count_matching_genes(genome1: &Genome, genome2: &Genome) -> i32 {
-        let mut matching_genes: i32 = 0;
-
-        let node_keys1 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome1.get_node_genes().keys()));
-        let node_keys2 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome2.get_node_genes().keys()));
-
-        let highest_innovation1 = node_keys1.get(node_keys1.len() - 1).unwrap();
-        let highest_innovation2 = node_keys2.get(node_keys2.len() - 1).unwrap();
-
-        let indices = cmp::max(highest_innovation1, highest_innovation2).clone();
-        for i in 0..indices {
-            let node_genes_genome1 = genome1.get_node_genes();
-            let node_genes_genome2 = genome2.get_node_genes();
-
-            let node1 = node_genes_genome1.get(&i);
-            let node2 = node_genes_genome2.get(&i);
-            let node1_exists = match node1 {
-                Some(_) => true,
-                None => false,
-            };
+        let suitable_connections: Vec<i32> = self
+            .connections
+            .iter()
+            .filter(|con| !self.cuts_off_io_node(|other| other.get_innovation() == con.get_innovation()))
+            .map(|con| con.get_innovation())
+            .collect();
 
-            let node2_exists = match node2 {
-                Some(_) => true,
-                None => false,
-            };
+        if suitable_connections.is_empty() {
+            return false;
+        }
 
-            // Check if both "exists"
-            if node1_exists && node2_exists {
-                // Both genomes has the gene w/ this innovation number
-                matching_genes += 1;
+        let innovation = suitable_connections[rng.gen_range(0, suitable_connections.len())];
+        let index = self
+            .connections
+            .binary_search_by_key(&innovation, |con| con.get_innovation())
+            .unwrap();
+        let removed = self.connections.remove(index);
+
+        // Node ids are never reused, so a removed node can't be confused with a newer one during crossover
+        for id in [removed.get_in_node(), removed.get_out_node()].iter() {
+            let is_hidden = self
+                .get_node_gene(*id)
+                .is_some_and(|node| node.get_type() == NodeGeneType::HIDDEN);
+            if is_hidden && self.count_node_connections(*id) == 0 {
+                self.nodes.retain(|node| node.get_id() != *id);
             }
         }
 
-        let con_keys1 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome1.get_connection_genes().keys()));
-        let con_keys2 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome2.get_connection_genes().keys()));
-
-        let highest_innovation1 = con_keys1.get(con_keys1.len() - 1).unwrap();
-        let highest_innovation2 = con_keys2.get(con_keys2.len() - 1).unwrap();
+        true
+    }
 
-        let indices = cmp::max(highest_innovation1, highest_innovation2).clone();
-        for i in 0..indices {
-            let connection_genes_genome1 = genome1.get_connection_genes();
-            let connection_genes_genome2 = genome2.get_connection_genes();
+    /// # delete_node_mutation
+    /// Removes a random hidden node along with every connection attached to it.
+    ///
+    /// Input and output nodes are never removed, and a hidden node is kept when it holds the last expressed connections of one of them.
+    /// Returns whether a node could be deleted.
+    pub fn delete_node_mutation(&mut self) -> bool {
+        let mut rng = rand::thread_rng();
 
-            let connection1 = connection_genes_genome1.get(&i);
-            let connection2 = connection_genes_genome2.get(&i);
+        let suitable_nodes: Vec<i32> = self
+            .nodes
+            .iter()
+            .filter(|node| node.get_type() == NodeGeneType::HIDDEN)
+            .filter(|node| {
+                let id = node.get_id();
+                !self.cuts_off_io_node(|con| con.get_in_node() == id || con.get_out_node() == id)
+            })
+            .map(|node| node.get_id())
+            .collect();
+
+        if suitable_nodes.is_empty() {
+            return false;
+        }
 
-            let con1_exists = match connection1 {
-                Some(_) => true,
-                None => false,
-            };
+        let id = suitable_nodes[rng.gen_range(0, suitable_nodes.len())];
+        self.connections
+            .retain(|con| con.get_in_node() != id && con.get_out_node() != id);
+        self.nodes.retain(|node| node.get_id() != id);
 
-            let con2_exists = match connection2 {
-                Some(_) => true,
-                None => false,
-            };
+        true
+    }
 
-            if con1_exists && con2_exists {
-                // both genomes has the gene w/ this innovation number
-                matching_genes += 1;
-            }
-        }
+    /// # count_node_connections
+    /// Number of connections going in or out of a node, expressed or not
+    fn count_node_connections(&self, id: i32) -> usize {
+        self.connections
+            .iter()
+            .filter(|con| con.get_in_node() == id || con.get_out_node() == id)
+            .count()
+    }
 
-        return matching_genes;
+    /// # cuts_off_io_node
+    /// Whether removing the connections matching `removed` leaves an input or output node without the expressed
+    /// connections it had, cutting it off in the phenotype. Disabled connections carry no signal, so they don't count
+    fn cuts_off_io_node<F: Fn(&ConnectionGene) -> bool>(&self, removed: F) -> bool {
+        self.connections
+            .iter()
+            .filter(|con| con.is_expressed() && removed(con))
+            .any(|con| {
+                [con.get_in_node(), con.get_out_node()].iter().any(|id| {
+                    let is_io = self
+                        .get_node_gene(*id)
+                        .is_some_and(|node| node.get_type() != NodeGeneType::HIDDEN);
+                    is_io
+                        && !self.connections.iter().any(|other| {
+                            other.is_expressed()
+                                && !removed(other)
+                                && (other.get_in_node() == *id || other.get_out_node() == *id)
+                        })
+                })
+            })
     }
 
-    pub fn count_disjoint_genes(genome1: &Genome, genome2: &Genome) -> i32 {
-        let mut disjoint_genes: i32 = 0;
-
-        let node_keys1 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome1.get_node_genes().keys()));
-        let node_keys2 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome2.get_node_genes().keys()));
-
-        let highest_innovation1 = node_keys1.get(node_keys1.len() - 1).unwrap();
-        let highest_innovation2 = node_keys2.get(node_keys2.len() - 1).unwrap();
-        let indices = cmp::max(highest_innovation1, highest_innovation2).clone();
-        for i in 0..indices {
-            let node_genes_genome1 = genome1.get_node_genes();
-            let node_genes_genome2 = genome2.get_node_genes();
-
-            let node1 = node_genes_genome1.get(&i);
-            let node2 = node_genes_genome2.get(&i);
-
-            let node1_exists = match node1 {
-                Some(_) => true,
-                None => false,
-            };
+    /// # crossover
+    /// ### Takes two parents and outputs a child.
+    ///
+    /// **parent_1** is always the more "fit" parent
+    ///
+    /// **parent_2** is the less "fit" parent
+    pub fn crossover(
+        parent_1: &Genome,
+        parent_2: &Genome,
+        disabled_gene_inheriting_chance: f32,
+    ) -> Genome {
+        // The paper's uniform crossover, with disjoint and excess genes from the fitter parent only
+        let mut config = Config::new(0, 0);
+        config.crossover = CrossoverMethod::Uniform;
+        config.disabled_gene_inheriting_chance = disabled_gene_inheriting_chance;
 
-            let node2_exists = match node2 {
-                Some(_) => true,
-                None => false,
-            };
+        Genome::crossover_with(parent_1, parent_2, false, &config)
+    }
 
-            if !node1_exists && highest_innovation1 < &i && node2_exists {
-                // Genome 1 lacks gene, genome 2 has gene, genome 1 has more genes with higher innovation numbers
-                disjoint_genes += 1;
-            } else if !node2_exists && highest_innovation2 < &i && node1_exists {
-                disjoint_genes += 1;
-            }
-        }
+    /// # crossover_with
+    /// Crossover with the `Config::crossover` operator, **parent_1** is the fitter parent or as fit as **parent_2**.
+    ///
+    /// Disjoint and excess genes come from parent_1, and also from parent_2 when `equal_fitness` is set and
+    /// `Config::crossover_equal_fitness_inherits_both` is on. Genes of parent_2 that would close a cycle are left out,
+    /// and matching genes parent_1 disabled stay disabled when expressing them would close one
+    pub fn crossover_with(
+        parent_1: &Genome,
+        parent_2: &Genome,
+        equal_fitness: bool,
+        config: &Config,
+    ) -> Genome {
+        let mut child = Genome::new();
+        let mut rng = rand::thread_rng();
 
-        let con_keys1 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome1.get_connection_genes().keys()));
-        let con_keys2 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome2.get_connection_genes().keys()));
-
-        let highest_innovation1 = con_keys1.get(node_keys1.len() - 1).unwrap();
-        let highest_innovation2 = con_keys2.get(node_keys2.len() - 1).unwrap();
-        let indices = cmp::max(highest_innovation1, highest_innovation2).clone();
-        for i in 0..indices {
-            let connection_genes_genome1 = genome1.get_connection_genes();
-            let connection_genes_genome2 = genome2.get_connection_genes();
-
-            let connection1 = connection_genes_genome1.get(&i);
-            let connection2 = connection_genes_genome2.get(&i);
-            let con1_exists = match connection1 {
-                Some(_) => true,
-                None => false,
-            };
+        child.nodes = parent_1.nodes.clone();
+        child.mutation_step = (parent_1.mutation_step + parent_2.mutation_step) / 2.0;
+        child.connections.reserve(parent_1.connections.len());
+
+        let from_both = equal_fitness && config.crossover_equal_fitness_inherits_both;
+        let points = match config.crossover {
+            CrossoverMethod::SinglePoint => 1,
+            CrossoverMethod::MultiPoint => config.crossover_points,
+            CrossoverMethod::Uniform | CrossoverMethod::Averaging => 0,
+        };
+        let points = Genome::crossover_points(parent_1, parent_2, points);
+
+        // Genes only parent_2 has are added once the structure of parent_1 is complete, so cycles can be checked
+        let mut parent_2_genes: Vec<ConnectionGene> = Vec::new();
+        // Indices of inherited genes to express once the structure of parent_1 is complete, for the same reason
+        let mut re_enabled: Vec<usize> = Vec::new();
+
+        // Walk the genes of both parents in innovation order, the crossover points are positions in this walk
+        let mut i = 0;
+        let mut j = 0;
+        let mut position = 0;
+        while i < parent_1.connections.len() || j < parent_2.connections.len() {
+            // Every point passed swaps the parent genes are taken from
+            let in_parent_2_segment = points.iter().filter(|point| **point <= position).count() % 2 == 1;
+            position += 1;
+
+            let parent_1_con = parent_1.connections.get(i);
+            let parent_2_con = parent_2.connections.get(j);
+            match (parent_1_con, parent_2_con) {
+                (Some(con_1), Some(con_2)) if con_1.get_innovation() == con_2.get_innovation() => {
+                    let mut child_con_gene = match config.crossover {
+                        CrossoverMethod::Uniform => {
+                            if rng.gen() {
+                                *con_1
+                            } else {
+                                *con_2
+                            }
+                        }
+                        CrossoverMethod::Averaging => {
+                            let mut gene = if rng.gen() { *con_1 } else { *con_2 };
+                            gene.set_weight((con_1.get_weight() + con_2.get_weight()) / 2.0);
+                            gene
+                        }
+                        CrossoverMethod::SinglePoint | CrossoverMethod::MultiPoint => {
+                            if in_parent_2_segment {
+                                *con_2
+                            } else {
+                                *con_1
+                            }
+                        }
+                    };
+
+                    let disabled = !con_1.is_expressed() || !con_2.is_expressed();
+                    if disabled && rng.gen::<f32>() < config.disabled_gene_inheriting_chance {
+                        child_con_gene.disable();
+                    }
 
-            let con2_exists = match connection2 {
-                Some(_) => true,
-                None => false,
-            };
+                    // A gene parent_1 disabled may close a cycle with its structure, it is enabled once that is complete
+                    if child_con_gene.is_expressed() && !con_1.is_expressed() {
+                        child_con_gene.disable();
+                        re_enabled.push(child.connections.len());
+                    }
 
-            if !con1_exists && highest_innovation1 > &i && con2_exists {
-                disjoint_genes += 1;
-            } else if !con2_exists && highest_innovation2 > &i && con1_exists {
-                disjoint_genes += 1;
+                    child.connections.push(child_con_gene);
+                    i += 1;
+                    j += 1;
+                }
+                (Some(con_1), con_2)
+                    if con_2.is_none_or(|con_2| con_1.get_innovation() < con_2.get_innovation()) =>
+                {
+                    // Parent_1 keeps its whole structure, so the inputs and outputs stay connected
+                    child.connections.push(*con_1);
+                    i += 1;
+                }
+                (_, Some(con_2)) => {
+                    // With point crossover only the segments of parent_2 bring in its own genes
+                    if from_both && (points.is_empty() || in_parent_2_segment) {
+                        parent_2_genes.push(*con_2);
+                    }
+                    j += 1;
+                }
+                (_, None) => unreachable!(),
             }
         }
 
-        disjoint_genes
-    }
-
-    pub fn count_excess_genes(genome1: &Genome, genome2: &Genome) -> i32 {
-        let mut excess_genes: i32 = 0;
-
-        let node_keys1 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome1.get_node_genes().keys()));
-        let node_keys2 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome2.get_node_genes().keys()));
-
-        let highest_innovation1 = node_keys1.get(node_keys1.len() - 1).unwrap();
-        let highest_innovation2 = node_keys2.get(node_keys2.len() - 1).unwrap();
-        let indices = cmp::max(highest_innovation1, highest_innovation2).clone();
-        for i in 0..indices {
-            let node_genes_genome1 = genome1.get_node_genes();
-            let node_genes_genome2 = genome2.get_node_genes();
-
-            let node1 = node_genes_genome1.get(&i);
-            let node2 = node_genes_genome2.get(&i);
-
-            let node1_exists = match node1 {
-                Some(_) => true,
-                None => false,
-            };
-
-            let node2_exists = match node2 {
-                Some(_) => true,
-                None => false,
-            };
-
-            if !node1_exists && highest_innovation1 < &i && node2_exists {
-                excess_genes += 1;
-            } else if !node2_exists && highest_innovation2 < &i && node1_exists {
-                excess_genes += 1;
+        // The expressed genes of parent_1 can't form a cycle, every other gene is checked against the child so far
+        for index in re_enabled {
+            let con = child.connections[index];
+            if !child.creates_cycle(con.get_in_node(), con.get_out_node()) {
+                child.connections[index].enable();
             }
         }
 
-        let con_keys1 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome1.get_connection_genes().keys()));
-        let con_keys2 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome2.get_connection_genes().keys()));
-
-        let highest_innovation1 = con_keys1.get(node_keys1.len() - 1).unwrap();
-        let highest_innovation2 = con_keys2.get(node_keys2.len() - 1).unwrap();
-        let indices = cmp::max(highest_innovation1, highest_innovation2).clone();
-        for i in 0..indices {
-            let connection_genes_genome1 = genome1.get_connection_genes();
-            let connection_genes_genome2 = genome2.get_connection_genes();
-
-            let connection1 = connection_genes_genome1.get(&i);
-            let connection2 = connection_genes_genome2.get(&i);
-            let con1_exists = match connection1 {
-                Some(_) => true,
-                None => false,
-            };
-
-            let con2_exists = match connection2 {
-                Some(_) => true,
-                None => false,
-            };
+        for con in parent_2_genes {
+            if con.is_expressed() && child.creates_cycle(con.get_in_node(), con.get_out_node()) {
+                continue;
+            }
 
-            if !con1_exists && highest_innovation1 < &i && con2_exists {
-                excess_genes += 1;
-            } else if !con2_exists && highest_innovation2 < &i && con1_exists {
-                excess_genes += 1;
+            for id in &[con.get_in_node(), con.get_out_node()] {
+                if child.get_node_gene(*id).is_none() {
+                    if let Some(node) = parent_2.get_node_gene(*id) {
+                        child.add_node_gene(*node);
+                    }
+                }
             }
+            child.add_connection_gene(con);
         }
-        excess_genes
+
+        child
     }
 
-    pub fn average_weight_diff(genome1: &Genome, genome2: &Genome) -> f32 {
-        let mut matching_genes: i32 = 0;
-        let mut weight_difference: f32 = 0.0;
+    /// # crossover_points
+    /// Sorted random positions in the innovation order walk of both parents, no more than there are genes to split
+    fn crossover_points(parent_1: &Genome, parent_2: &Genome, count: usize) -> Vec<usize> {
+        let comparison = Genome::compare_genes(parent_1, parent_2);
+        let genes = comparison.matching + comparison.disjoint + comparison.excess;
+        if genes < 2 || count == 0 {
+            return Vec::new();
+        }
 
-        let con_keys1 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome1.get_connection_genes().keys()));
-        let con_keys2 =
-            Genome::as_sorted_vec(Genome::keys_to_vec(genome2.get_connection_genes().keys()));
+        // A point at position p starts a new segment with the p-th gene, the first segment is never empty
+        let mut rng = rand::thread_rng();
+        let mut points: Vec<usize> = rand::seq::index::sample(&mut rng, genes - 1, count.min(genes - 1))
+            .into_iter()
+            .map(|point| point + 1)
+            .collect();
+        points.sort_unstable();
 
-        let highest_innovation1 = con_keys1.get(con_keys1.len() - 1).unwrap();
-        let highest_innovation2 = con_keys2.get(con_keys2.len() - 1).unwrap();
-        let indices = cmp::max(highest_innovation1, highest_innovation2).clone();
-        for i in 0..indices {
-            let connection_genes_genome1 = genome1.get_connection_genes();
-            let connection_genes_genome2 = genome2.get_connection_genes();
+        points
+    }
 
-            let connection1 = connection_genes_genome1.get(&i);
-            let connection2 = connection_genes_genome2.get(&i);
+    /// # compatibility_distance
+    /// How identical two genomes are, identical ones will output a zero,
+    /// [article describing the equation.](http://nn.cs.utexas.edu/downloads/papers/stanley.ec02.pdf)
+    ///
+    /// C1, C2, and C3 are importance coefficients, the excess and disjoint terms are divided by the number of genes
+    /// in the larger genome once it has at least `normalisation_threshold` genes
+    pub fn compatibility_distance(
+        genome1: &Genome,
+        genome2: &Genome,
+        c1: f32,
+        c2: f32,
+        c3: f32,
+        normalisation_threshold: usize,
+    ) -> f32 {
+        let comparison = Genome::compare_genes(genome1, genome2);
 
-            let con1_exists = match connection1 {
-                Some(_) => true,
-                None => false,
-            };
+        // Number of genes in the larger genome, normalizes for genome size
+        // (set to 1 if both genomes are small, i.e, consists of fewer than 20 genes)
+        let larger = cmp::max(genome1.connections.len(), genome2.connections.len());
+        let n = if larger >= normalisation_threshold {
+            larger as f32
+        } else {
+            1.0
+        };
+
+        ((c1 * comparison.excess as f32) / n)
+            + ((c2 * comparison.disjoint as f32) / n)
+            + c3 * comparison.mean_weight_difference
+    }
 
-            let con2_exists = match connection2 {
-                Some(_) => true,
-                None => false,
-            };
+    /// # compare_genes
+    /// Lines up the connection genes of two genomes by innovation number in a single pass.
+    ///
+    /// Genes missing in one genome are disjoint while they are within the innovation range of the other genome,
+    /// and excess once they are past it.
+    pub fn compare_genes(genome1: &Genome, genome2: &Genome) -> GeneComparison {
+        let connections1 = &genome1.connections;
+        let connections2 = &genome2.connections;
+
+        let mut comparison = GeneComparison {
+            matching: 0,
+            disjoint: 0,
+            excess: 0,
+            mean_weight_difference: 0.0,
+        };
+        let mut weight_difference: f32 = 0.0;
 
-            if con1_exists && con2_exists {
-                // both genomes has the gene w/ this innovation number
-                matching_genes += 1;
-                weight_difference +=
-                    (connection1.unwrap().get_weight() - connection2.unwrap().get_weight()).abs();
+        let mut i = 0;
+        let mut j = 0;
+        while i < connections1.len() && j < connections2.len() {
+            let con1 = &connections1[i];
+            let con2 = &connections2[j];
+
+            match con1.get_innovation().cmp(&con2.get_innovation()) {
+                cmp::Ordering::Equal => {
+                    // Both genomes has the gene w/ this innovation number
+                    comparison.matching += 1;
+                    weight_difference += (con1.get_weight() - con2.get_weight()).abs();
+                    i += 1;
+                    j += 1;
+                }
+                cmp::Ordering::Less => {
+                    comparison.disjoint += 1;
+                    i += 1;
+                }
+                cmp::Ordering::Greater => {
+                    comparison.disjoint += 1;
+                    j += 1;
+                }
             }
         }
 
-        weight_difference / (matching_genes as f32)
+        // Whatever is left of the longer genome lies past the end of the other one
+        comparison.excess = (connections1.len() - i) + (connections2.len() - j);
+
+        if comparison.matching > 0 {
+            comparison.mean_weight_difference = weight_difference / comparison.matching as f32;
+        }
+
+        comparison
     }
 
-    /// # as_sorted_vec
-    /// Sorts a vector in a ascending order
-    pub fn as_sorted_vec<K>(c: Vec<K>) -> Vec<K>
-    where
-        K: Clone,
-        K: Ord,
-    {
-        let mut c: Vec<K> = c.iter().map(|e| e.clone()).collect();
-        c.sort_by(|a, b| a.cmp(&b));
+    pub fn count_matching_genes(genome1: &Genome, genome2: &Genome) -> i32 {
+        Genome::compare_genes(genome1, genome2).matching as i32
+    }
 
-        c
+    pub fn count_disjoint_genes(genome1: &Genome, genome2: &Genome) -> i32 {
+        Genome::compare_genes(genome1, genome2).disjoint as i32
     }
 
-    /// # keys_to_vec
-    /// Takes HashMap keys and converts them to a owned vector
-    pub fn keys_to_vec<K, V>(c: Keys<'_, K, V>) -> Vec<K>
-    where
-        K: Clone,
-    {
-        // Might be slow as we are allocating memory every time the function is called
-        let mut keys: Vec<K> = Vec::new();
-        for k in c {
-            keys.push(k.clone());
-        }
+    pub fn count_excess_genes(genome1: &Genome, genome2: &Genome) -> i32 {
+        Genome::compare_genes(genome1, genome2).excess as i32
+    }
 
-        keys
+    pub fn average_weight_diff(genome1: &Genome, genome2: &Genome) -> f32 {
+        Genome::compare_genes(genome1, genome2).mean_weight_difference
     }
 }
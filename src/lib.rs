@@ -4,11 +4,18 @@ pub use genome::connection_gene::ConnectionGene;
 pub use genome::node_gene::NodeGene;
 pub use genome::node_gene::NodeGeneType;
 pub use genome::Genome;
+pub use genome::StartingTopology;
+pub use genome::GeneComparison;
 
 #[path = "./debugging/printer.rs"]
 pub mod printer;
 pub use printer::GenomePrinter;
 
+#[path = "./debugging/report.rs"]
+pub mod report;
+pub use report::ReportPrinter;
+pub use report::TerminationReason;
+
 #[path = "./genome/counter.rs"]
 pub mod counter;
 pub use counter::Counter;
@@ -16,7 +23,42 @@ pub use counter::Counter;
 #[path = "./evaluator/evaluator.rs"]
 pub mod evaluator;
 pub use evaluator::Evaluator;
+pub use evaluator::FitnessGenomeProvider;
+pub use evaluator::GenesisGenomeProvider;
+pub use evaluator::SearchPhase;
+pub use evaluator::novelty::ArchiveInsertion;
+pub use evaluator::novelty::NoveltyArchive;
+pub use evaluator::selection::Selection;
+pub use evaluator::selection::SelectionMethod;
+pub use evaluator::species::Species;
+pub use evaluator::statistics::GenerationStatistics;
 
 #[path = "./config.rs"]
 pub mod config;
-pub use config::Config;
\ No newline at end of file
+pub use config::Config;
+pub use config::CrossoverMethod;
+pub use config::WeightMutation;
+
+#[path = "./network/network.rs"]
+pub mod network;
+pub use network::Network;
+pub use network::compiled::CompiledNetwork;
+
+#[path = "./tasks/tasks.rs"]
+pub mod tasks;
+pub use tasks::dataset::Dataset;
+pub use tasks::dataset::DatasetTask;
+pub use tasks::dataset::Metric;
+pub use tasks::environment::EpisodeRunner;
+pub use tasks::environment::Environment;
+pub use tasks::pole_balancing::PoleBalancing;
+pub use tasks::pole_balancing::PoleBalancingTask;
+pub use tasks::xor::XorTask;
+
+#[path = "./storage/storage.rs"]
+pub mod storage;
+pub use storage::Checkpoint;
+
+#[path = "./export/export.rs"]
+pub mod export;
+pub use export::Language;
\ No newline at end of file
@@ -1,4 +1,4 @@
-#![allow(unused_variables, dead_code, unused_imports, unused_assignments)]
+#![allow(unused_variables, dead_code, unused_assignments)]
 /// Implementation of NEAT algorithm in Rust
 /// Developed by **StarToLeft**
 ///
@@ -6,128 +6,578 @@
 /// http://nn.cs.utexas.edu/downloads/papers/stanley.ec02.pdf
 ///
 /// Made in 2020-07-03 in Gothenburg, Sweden.
-mod lib;
-use lib::evaluator::FitnessGenomeProvider;
-use lib::evaluator::GenesisGenomeProvider;
-use lib::Config;
-use lib::ConnectionGene;
-use lib::Counter;
-use lib::Evaluator;
-use lib::Genome;
-use lib::GenomePrinter;
-use lib::NodeGene;
-use lib::NodeGeneType;
-
-use rand::Rng;
+use neat_java::evaluator::FitnessGenomeProvider;
+use neat_java::evaluator::GenesisGenomeProvider;
+use neat_java::export;
+use neat_java::storage;
+use neat_java::Checkpoint;
+use neat_java::Config;
+use neat_java::Counter;
+use neat_java::Dataset;
+use neat_java::DatasetTask;
+use neat_java::Evaluator;
+use neat_java::Genome;
+use neat_java::GenomePrinter;
+use neat_java::Language;
+use neat_java::Metric;
+use neat_java::NodeGeneType;
+use neat_java::PoleBalancing;
+use neat_java::PoleBalancingTask;
+use neat_java::ReportPrinter;
+use neat_java::StartingTopology;
+use neat_java::TerminationReason;
+use neat_java::XorTask;
+
 use rand_distr::{Distribution, Normal};
 
 use std::env;
+use std::process;
+use std::rc::Rc;
+
+const USAGE: &str = "Usage:
+    neat-java run --task <task> [--config <file>] [--topology <topology>] [--hidden <nodes>] [--output <name>] [--target-fitness <fitness>] [--checkpoint-every <generations>]
+    neat-java resume <checkpoint> [--generations <count>] [--output <name>] [--target-fitness <fitness>] [--checkpoint-every <generations>]
+    neat-java render <genome> <name> [--format png|svg|dot]
+    neat-java export <genome> <name> [--language rust|c]
+    neat-java inspect <genome>
+    neat-java compare <genome> <genome> [--config <file>]
+
+Tasks:
+    xor
+    single-pole
+    double-pole
+    double-pole-no-velocities
+    dataset:<csv file>:<input columns>:<output columns>[:mse|cross-entropy|accuracy]
+        columns are zero based and comma separated, e.g. dataset:iris.csv:0,1,2,3:4,5,6:accuracy
+
+Topologies:
+    full (default), sparse:<fraction>, unconnected, fs-neat
+    --hidden adds a fully connected hidden layer of that many nodes instead
+
+Every file is written to ./output, <name> defaults to \"run\".";
 
 // Genesis provider
-pub struct GenesisProvider {}
+pub struct GenesisProvider {
+    input_count: usize,
+    output_count: usize,
+    topology: StartingTopology,
+    // Size of the initial hidden layer, the topology is ignored when there is one
+    hidden_count: usize,
+}
 
 impl GenesisProvider {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(
+        input_count: usize,
+        output_count: usize,
+        topology: StartingTopology,
+        hidden_count: usize,
+    ) -> Self {
+        Self {
+            input_count,
+            output_count,
+            topology,
+            hidden_count,
+        }
+    }
+
+    pub fn starting_genome(&self, node_innovation: &mut Counter, connection_innovation: &mut Counter) -> Genome {
+        if self.hidden_count > 0 {
+            Genome::with_hidden_layer(
+                self.input_count,
+                self.output_count,
+                self.hidden_count,
+                node_innovation,
+                connection_innovation,
+            )
+        } else {
+            Genome::minimal(
+                self.input_count,
+                self.output_count,
+                self.topology,
+                node_innovation,
+                connection_innovation,
+            )
+        }
     }
 }
 
 impl GenesisGenomeProvider for GenesisProvider {
     fn generate_genesis_genome(&self, genome: &Genome) -> Genome {
-        for connection in genome.get_connection_genes().values_mut() {
+        // Every member gets its own connections, the numbering of the constructors keeps them lined up
+        let mut genome = self.starting_genome(&mut Counter::new(), &mut Counter::new());
+
+        for connection in genome.get_connection_genes_mut() {
             let normal = Normal::new(0.0, 1.0).unwrap();
             let v = normal.sample(&mut rand::thread_rng());
 
             connection.set_weight(v);
         }
 
-        return genome.to_owned();
+        genome
     }
 }
 
-// Genome fitness evaluator provider
-pub struct GenomeFitnessProvider {}
+// Shares one fitness provider between generations, as the evaluator takes ownership of a provider every generation
+pub struct SharedFitnessProvider {
+    provider: Rc<dyn FitnessGenomeProvider>,
+}
+
+impl FitnessGenomeProvider for SharedFitnessProvider {
+    fn fitness_genome_evaluator(&self, genome: &Genome) -> f32 {
+        self.provider.fitness_genome_evaluator(genome)
+    }
+
+    fn fitness_and_behaviour(&self, genome: &Genome) -> (f32, Vec<f32>) {
+        self.provider.fitness_and_behaviour(genome)
+    }
 
-impl GenomeFitnessProvider {
-    pub fn new() -> Self {
-        Self {}
+    fn has_behaviour(&self) -> bool {
+        self.provider.has_behaviour()
     }
 }
 
-impl FitnessGenomeProvider for GenomeFitnessProvider {
-    fn fitness_genome_evaluator(&self, genome: &Genome) -> f32 {
-        return genome.get_connection_genes().len() as f32;
+// A built-in task parsed from the command line
+pub struct Task {
+    provider: Rc<dyn FitnessGenomeProvider>,
+    dataset: Option<Rc<DatasetTask>>,
+
+    input_count: usize,
+    output_count: usize,
+}
+
+impl Task {
+    pub fn from_spec(spec: &str) -> Result<Task, String> {
+        let pole_balancing = |variant: PoleBalancing, max_steps: usize| {
+            let task = PoleBalancingTask::new(variant, max_steps);
+            Task {
+                input_count: task.get_input_count(),
+                output_count: task.get_output_count(),
+                provider: Rc::new(task),
+                dataset: None,
+            }
+        };
+
+        match spec {
+            "xor" => Ok(Task {
+                provider: Rc::new(XorTask::new()),
+                dataset: None,
+                input_count: XorTask::INPUT_COUNT,
+                output_count: XorTask::OUTPUT_COUNT,
+            }),
+            "single-pole" => Ok(pole_balancing(PoleBalancing::SinglePole, 100_000)),
+            "double-pole" => Ok(pole_balancing(PoleBalancing::DoublePole, 100_000)),
+            "double-pole-no-velocities" => Ok(pole_balancing(
+                PoleBalancing::DoublePoleNoVelocities,
+                1000,
+            )),
+            _ if spec.starts_with("dataset:") => {
+                let parts: Vec<&str> = spec.split(':').collect();
+                if parts.len() < 4 {
+                    return Err(format!("expected dataset:<csv file>:<inputs>:<outputs>, got '{}'", spec));
+                }
+
+                let columns = |text: &str| -> Result<Vec<usize>, String> {
+                    text.split(',')
+                        .map(|column| {
+                            column
+                                .trim()
+                                .parse::<usize>()
+                                .map_err(|_| format!("'{}' is not a column index", column))
+                        })
+                        .collect()
+                };
+                let metric = match parts.get(4) {
+                    None | Some(&"mse") => Metric::MeanSquaredError,
+                    Some(&"cross-entropy") => Metric::CrossEntropy,
+                    Some(&"accuracy") => Metric::Accuracy,
+                    Some(other) => return Err(format!("unknown metric '{}'", other)),
+                };
+
+                let input_columns = columns(parts[2])?;
+                let output_columns = columns(parts[3])?;
+
+                // Treat the first line as a header when one of the selected columns is not numeric
+                let text = std::fs::read_to_string(parts[1]).map_err(|e| e.to_string())?;
+                let has_header = text.lines().next().is_some_and(|line| {
+                    let fields: Vec<&str> = line.split(',').collect();
+                    input_columns.iter().chain(&output_columns).any(|column| {
+                        fields
+                            .get(*column)
+                            .is_some_and(|field| field.trim().parse::<f32>().is_err())
+                    })
+                });
+                let dataset = Dataset::from_csv_str(&text, &input_columns, &output_columns, has_header)
+                    .map_err(|e| e.to_string())?;
+
+                let task = Rc::new(DatasetTask::new(&dataset, 0.2, metric));
+                Ok(Task {
+                    input_count: task.get_input_count(),
+                    output_count: task.get_output_count(),
+                    provider: task.clone(),
+                    dataset: Some(task),
+                })
+            }
+            _ => Err(format!("unknown task '{}'", spec)),
+        }
+    }
+
+    pub fn fitness_provider(&self) -> Box<dyn FitnessGenomeProvider> {
+        Box::new(SharedFitnessProvider {
+            provider: self.provider.clone(),
+        })
     }
 }
 
-fn main() {
-    let timer = std::time::Instant::now();
+// Value following a flag, e.g. `--config file.txt`
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+}
+
+// Arguments that are neither flags nor flag values
+fn positional(args: &[String]) -> Vec<String> {
+    let mut positional = Vec::new();
+    let mut skip = false;
+    for arg in args {
+        if skip {
+            skip = false;
+        } else if arg.starts_with("--") {
+            skip = true;
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    positional
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(1);
+}
 
+fn parse_flag<T: std::str::FromStr>(args: &[String], flag: &str) -> Option<T> {
+    flag_value(args, flag).map(|value| {
+        value
+            .parse::<T>()
+            .unwrap_or_else(|_| fail(&format!("invalid value '{}' for {}", value, flag)))
+    })
+}
+
+fn load_genome(path: &str) -> Genome {
+    storage::load_genome(path).unwrap_or_else(|e| fail(&format!("could not read {}: {}", path, e)))
+}
+
+// Options shared by `run` and `resume`
+struct RunOptions {
+    task_spec: String,
+    output: String,
+    generations: usize,
+    target_fitness: Option<f32>,
+    checkpoint_every: Option<usize>,
+}
+
+fn evolve(
+    evaluator: &mut Evaluator,
+    task: &Task,
+    options: &RunOptions,
+    node_innovation: &mut Counter,
+    connection_innovation: &mut Counter,
+) {
+    if evaluator.get_config().novelty_search && !task.provider.has_behaviour() {
+        fail(&format!("the task '{}' has no behaviour descriptor for novelty search", options.task_spec));
+    }
+
+    let timer = std::time::Instant::now();
     let mut printer = GenomePrinter::new();
+    let mut termination = TerminationReason::GenerationLimit;
+
+    let checkpoint = |evaluator: &Evaluator, node_innovation: &Counter, connection_innovation: &Counter| {
+        let path = format!("./output/{}.checkpoint", options.output);
+        Checkpoint::new(evaluator, node_innovation, connection_innovation, &options.task_spec)
+        .save(&path)
+        .unwrap_or_else(|e| fail(&format!("could not write {}: {}", path, e)));
+    };
+
+    for _ in 0..options.generations {
+        // Evaluate the generation
+        evaluator
+            .evaluate_generation(task.fitness_provider(), node_innovation, connection_innovation)
+            .unwrap_or_else(|e| fail(&e));
+
+        let generation = evaluator.get_generation();
+        let fittest = evaluator.get_fittest_genome();
 
-    let mut genome = Genome::new();
+        println!("Generation: {}", generation);
+        println!("\t Highest fitness: {}", fittest.get_objective_fitness());
+        println!("\t Amount of genomes: {}", evaluator.get_genome_amount());
+        println!("\t Amount of species: {}", evaluator.get_species().len());
+        if evaluator.get_config().dynamic_dt {
+            println!("\t Compatibility threshold: {}", evaluator.get_compatibility_threshold());
+        }
+        if evaluator.get_config().novelty_search {
+            println!(
+                "\t Novelty archive: {}",
+                evaluator.get_novelty_archive().get_behaviours().len()
+            );
+        }
+        if evaluator.get_config().phased_search {
+            println!("\t Phase: {:?}", evaluator.get_phase());
+        }
+
+        // Record the champion as a frame of the timeline
+        printer.record_frame(fittest.get_genome(), &format!("Generation {}", generation));
+
+        if let Some(every) = options.checkpoint_every {
+            if every > 0 && generation.is_multiple_of(every) {
+                checkpoint(evaluator, node_innovation, connection_innovation);
+            }
+        }
+
+        if let Some(target) = options.target_fitness {
+            if fittest.get_objective_fitness() >= target {
+                termination = TerminationReason::FitnessReached(target);
+                break;
+            }
+        }
+    }
+
+    let champion = evaluator.get_fittest_genome();
+    if let Some(dataset) = &task.dataset {
+        println!(
+            "Validation fitness of the champion: {}",
+            dataset.validation_fitness(champion.get_genome())
+        );
+    }
+
+    checkpoint(evaluator, node_innovation, connection_innovation);
 
-    let mut connection_innovation: Counter = Counter::new();
-    let mut node_innovation: Counter = Counter::new();
+    let champion_path = format!("./output/{}_champion.genome", options.output);
+    storage::save_genome(champion.get_genome(), &champion_path)
+        .unwrap_or_else(|e| fail(&format!("could not write {}: {}", champion_path, e)));
 
-    // Nodes
-    let n1 = node_innovation.get_innovation();
-    let n2 = node_innovation.get_innovation();
-    let n3 = node_innovation.get_innovation();
+    // Print the champions of every generation as one animation and summarize the run
+    printer.print_timeline(&format!("{}_timeline", options.output), 200);
+    ReportPrinter::new().print_report(evaluator, termination, &format!("{}_report", options.output));
+
+    println!("{}", termination);
+    println!("Finished after {:?}", timer.elapsed());
+}
 
-    let input1 = NodeGene::new(NodeGeneType::INPUT, n1);
-    let input2 = NodeGene::new(NodeGeneType::INPUT, n2);
-    let output = NodeGene::new(NodeGeneType::OUTPUT, n3);
+fn run(args: &[String]) {
+    let task_spec = flag_value(args, "--task").unwrap_or_else(|| fail("run needs a --task"));
+    let task = Task::from_spec(&task_spec).unwrap_or_else(|e| fail(&e));
 
-    genome.add_node_gene(input1);
-    genome.add_node_gene(input2);
-    genome.add_node_gene(output);
+    let config = match flag_value(args, "--config") {
+        Some(path) => Config::from_file(&path)
+            .unwrap_or_else(|e| fail(&format!("could not read {}: {}", path, e))),
+        None => Config::new(150, 100),
+    };
 
-    // Connections
-    let c1 = connection_innovation.get_innovation();
-    let c2 = connection_innovation.get_innovation();
-    genome.add_connection_gene(ConnectionGene::new(n1, n3, 0.5, true, c1));
-    genome.add_connection_gene(ConnectionGene::new(n2, n3, 0.5, true, c1));
+    let topology = match flag_value(args, "--topology").as_deref() {
+        None | Some("full") => StartingTopology::FullyConnected,
+        Some("unconnected") => StartingTopology::Unconnected,
+        Some("fs-neat") => StartingTopology::FsNeat,
+        Some(other) if other.starts_with("sparse:") => StartingTopology::Sparse(
+            other["sparse:".len()..]
+                .parse::<f32>()
+                .unwrap_or_else(|_| fail(&format!("invalid fraction in '{}'", other))),
+        ),
+        Some(other) => fail(&format!("unknown topology '{}'", other)),
+    };
 
-    // Configuration
-    // Assign a starting population and generation count
-    let config: Config = Config::new(10, 1000);
+    let hidden_count: usize = parse_flag(args, "--hidden").unwrap_or(0);
+    let genesis_provider =
+        GenesisProvider::new(task.input_count, task.output_count, topology, hidden_count);
 
-    // Genesis provider
-    let provider = GenesisProvider::new();
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = genesis_provider.starting_genome(&mut node_innovation, &mut connection_innovation);
 
-    // Initialize the genome evaluator
     let mut evaluator = Evaluator::new();
-    evaluator.init(&config, &genome, Box::new(provider));
+    evaluator.init(&config, &genome, Box::new(genesis_provider));
 
-    for i in 1..config.get_generation_count() + 1 {
-        // Fitness provider
-        let fitness_provider = GenomeFitnessProvider::new();
+    let options = RunOptions {
+        task_spec,
+        output: flag_value(args, "--output").unwrap_or_else(|| String::from("run")),
+        generations: config.get_generation_count(),
+        target_fitness: parse_flag(args, "--target-fitness"),
+        checkpoint_every: parse_flag(args, "--checkpoint-every"),
+    };
 
-        // Evaluate the generation
-        evaluator.evaluate_generation(
-            Box::new(fitness_provider),
-            &mut node_innovation,
-            &mut connection_innovation,
-        );
+    evolve(
+        &mut evaluator,
+        &task,
+        &options,
+        &mut node_innovation,
+        &mut connection_innovation,
+    );
+}
+
+fn resume(args: &[String]) {
+    let path = positional(args)
+        .first()
+        .cloned()
+        .unwrap_or_else(|| fail("resume needs a checkpoint"));
+    let checkpoint = Checkpoint::load(&path)
+        .unwrap_or_else(|e| fail(&format!("could not read {}: {}", path, e)));
+    let task = Task::from_spec(&checkpoint.task).unwrap_or_else(|e| fail(&e));
+
+    let mut node_innovation = Counter::starting_at(checkpoint.node_innovation);
+    let mut connection_innovation = Counter::starting_at(checkpoint.connection_innovation);
+
+    let mut evaluator = Evaluator::new();
+    evaluator.resume(&checkpoint);
+
+    let remaining = checkpoint
+        .config
+        .get_generation_count()
+        .saturating_sub(checkpoint.generation);
+    let options = RunOptions {
+        task_spec: checkpoint.task,
+        output: flag_value(args, "--output").unwrap_or_else(|| String::from("run")),
+        generations: parse_flag(args, "--generations").unwrap_or(remaining),
+        target_fitness: parse_flag(args, "--target-fitness"),
+        checkpoint_every: parse_flag(args, "--checkpoint-every"),
+    };
+
+    evolve(
+        &mut evaluator,
+        &task,
+        &options,
+        &mut node_innovation,
+        &mut connection_innovation,
+    );
+}
+
+fn render(args: &[String]) {
+    let positional = positional(args);
+    if positional.len() < 2 {
+        fail("render needs a genome and a name");
+    }
+
+    let mut genome = load_genome(&positional[0]);
+    let name = &positional[1];
+    let mut printer = GenomePrinter::new();
+
+    match flag_value(args, "--format").as_deref() {
+        None | Some("png") => printer.print_genome(&mut genome, name, name),
+        Some("svg") => printer.print_svg(&genome, name),
+        Some("dot") => printer.print_dot(&genome, name),
+        Some(other) => fail(&format!("unknown format '{}'", other)),
+    }
+}
+
+fn export(args: &[String]) {
+    let positional = positional(args);
+    if positional.len() < 2 {
+        fail("export needs a genome and a name");
+    }
+
+    let genome = load_genome(&positional[0]);
+    let name = &positional[1];
+    let language = match flag_value(args, "--language").as_deref() {
+        None | Some("rust") => Language::Rust,
+        Some("c") => Language::C,
+        Some(other) => fail(&format!("unknown language '{}'", other)),
+    };
+
+    let path = format!("./output/{}.{}", name, language.get_extension());
+    export::save_source(&genome, name, language, &path)
+        .unwrap_or_else(|e| fail(&format!("could not write {}: {}", path, e)));
+    println!("Wrote {}", path);
+}
 
-        println!("Generation: {}", i);
+fn inspect(args: &[String]) {
+    let path = positional(args)
+        .first()
+        .cloned()
+        .unwrap_or_else(|| fail("inspect needs a genome"));
+    let genome = load_genome(&path);
+
+    let nodes = genome.get_node_genes();
+    let connections = genome.get_connection_genes();
+    let count_nodes = |node_type: NodeGeneType| {
+        nodes
+            .iter()
+            .filter(|node| node.get_type() == node_type)
+            .count()
+    };
+    let expressed = connections.iter().filter(|con| con.is_expressed()).count();
+    let weights: Vec<f32> = connections.iter().map(|con| con.get_weight()).collect();
+
+    println!("Genome: {}", path);
+    println!("\t Nodes: {}", nodes.len());
+    println!("\t\t Inputs: {}", count_nodes(NodeGeneType::INPUT));
+    println!("\t\t Hidden: {}", count_nodes(NodeGeneType::HIDDEN));
+    println!("\t\t Outputs: {}", count_nodes(NodeGeneType::OUTPUT));
+    println!("\t Connections: {}", connections.len());
+    println!("\t\t Expressed: {}", expressed);
+    println!("\t\t Disabled: {}", connections.len() - expressed);
+    println!(
+        "\t Highest innovation: {}",
+        connections.last().map_or(0, |con| con.get_innovation())
+    );
+    if !weights.is_empty() {
         println!(
-            "\t Highest fitness: {}",
-            evaluator.get_fittest_genome().get_fitness()
+            "\t Weights: min {}, max {}, mean {}",
+            weights.iter().copied().fold(f32::MAX, f32::min),
+            weights.iter().copied().fold(f32::MIN, f32::max),
+            weights.iter().sum::<f32>() / weights.len() as f32
         );
-        println!("\t Amount of genomes: {}", evaluator.get_genome_amount());
+    }
+}
 
-        // Print populations
-        let mut fittest_genome = evaluator.get_fittest_genome().get_genome();
+fn compare(args: &[String]) {
+    let positional = positional(args);
+    if positional.len() < 2 {
+        fail("compare needs two genomes");
+    }
 
-        let _i = i.to_string();
-        let mut name = String::from("genome_");
-        name.push_str(&_i);
+    let genome1 = load_genome(&positional[0]);
+    let genome2 = load_genome(&positional[1]);
+    let config = match flag_value(args, "--config") {
+        Some(path) => Config::from_file(&path)
+            .unwrap_or_else(|e| fail(&format!("could not read {}: {}", path, e))),
+        None => Config::new(0, 0),
+    };
 
-        printer.print_genome(&mut fittest_genome, &name, &name);
-    }
+    let comparison = Genome::compare_genes(&genome1, &genome2);
+    let distance = Genome::compatibility_distance(
+        &genome1,
+        &genome2,
+        config.c1,
+        config.c2,
+        config.c3,
+        config.compatibility_normalisation_threshold,
+    );
 
-    let time = timer.elapsed();
-    println!("Finished after {:?}", time);
+    println!("Matching genes: {}", comparison.matching);
+    println!("Disjoint genes: {}", comparison.disjoint);
+    println!("Excess genes: {}", comparison.excess);
+    println!("Mean weight difference: {}", comparison.mean_weight_difference);
+
+    println!("Compatibility distance: {}", distance);
+    println!(
+        "\t Same species: {}",
+        if distance < config.dt { "yes" } else { "no" }
+    );
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(|arg| arg.as_str()) {
+        Some("run") => run(&args[1..]),
+        Some("resume") => resume(&args[1..]),
+        Some("render") => render(&args[1..]),
+        Some("export") => export(&args[1..]),
+        Some("inspect") => inspect(&args[1..]),
+        Some("compare") => compare(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => println!("{}", USAGE),
+        Some(other) => fail(&format!("unknown command '{}'", other)),
+        None => fail("missing command"),
+    }
 }
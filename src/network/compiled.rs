@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use super::sigmoid;
+use crate::Genome;
+use crate::Network;
+
+/// # CompiledNetwork
+/// A flattened `Network` for fast inference.
+///
+/// Nodes are renumbered into a dense array: the inputs first, then the hidden and output nodes in topological order.
+/// Expressed connections are stored as contiguous source, target and weight arrays sorted by target,
+/// so a forward pass is a single loop over plain arrays.
+#[derive(Debug, Clone)]
+pub struct CompiledNetwork {
+    input_count: usize,
+    // End of the nodes that are computed, nodes after it are part of a cycle and stay at zero
+    computed_count: usize,
+    // Index of every output node in the values array
+    outputs: Vec<usize>,
+
+    sources: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Vec<f32>,
+
+    values: Vec<f32>,
+
+    // Buffers of `predict_batch`, node major so every row of a node is contiguous
+    batch_values: Vec<f32>,
+    batch_sums: Vec<f32>,
+}
+
+impl CompiledNetwork {
+    pub fn from_genome(genome: &Genome) -> CompiledNetwork {
+        CompiledNetwork::from_network(&Network::from_genome(genome))
+    }
+
+    /// # from_network
+    /// Compiles a network, the compiled form gives exactly the same outputs
+    pub fn from_network(network: &Network) -> CompiledNetwork {
+        // Dense index of every node, inputs then the topological order
+        let mut index: HashMap<i32, usize> = HashMap::new();
+        for id in network.inputs.iter().chain(network.order.iter()) {
+            let next = index.len();
+            index.entry(*id).or_insert(next);
+        }
+        let computed_count = index.len();
+
+        // Outputs that were never reached get a slot of their own that stays at zero
+        for id in &network.outputs {
+            let next = index.len();
+            index.entry(*id).or_insert(next);
+        }
+
+        let mut sources: Vec<usize> = Vec::new();
+        let mut targets: Vec<usize> = Vec::new();
+        let mut weights: Vec<f32> = Vec::new();
+        for id in &network.order {
+            if let Some(incoming) = network.incoming.get(id) {
+                for (in_node, weight) in incoming {
+                    // Connections from nodes that are never computed always add zero
+                    if let Some(source) = index.get(in_node) {
+                        sources.push(*source);
+                        targets.push(index[id]);
+                        weights.push(*weight);
+                    }
+                }
+            }
+        }
+
+        CompiledNetwork {
+            input_count: network.inputs.len(),
+            computed_count,
+            outputs: network.outputs.iter().map(|id| index[id]).collect(),
+            sources,
+            targets,
+            weights,
+            values: vec![0.0; index.len()],
+
+            batch_values: Vec::new(),
+            batch_sums: Vec::new(),
+        }
+    }
+
+    /// # activate
+    /// Runs a single forward pass and returns the values of the output nodes
+    pub fn activate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        for value in self.values.iter_mut() {
+            *value = 0.0;
+        }
+        let given = inputs.len().min(self.input_count);
+        self.values[..given].copy_from_slice(&inputs[..given]);
+
+        let mut c = 0;
+        for node in self.input_count..self.computed_count {
+            let mut sum = 0.0;
+            while c < self.targets.len() && self.targets[c] == node {
+                sum += self.values[self.sources[c]] * self.weights[c];
+                c += 1;
+            }
+            self.values[node] = sigmoid(sum);
+        }
+
+        self.outputs.iter().map(|index| self.values[*index]).collect()
+    }
+
+    /// # predict_batch
+    /// Runs a forward pass for `rows` rows at once.
+    ///
+    /// `inputs` holds the rows one after another, `get_input_count()` values each,
+    /// and the outputs are returned the same way with `get_output_count()` values per row.
+    /// The outputs are identical to calling `activate` on every row.
+    pub fn predict_batch(&mut self, inputs: &[f32], rows: usize) -> Vec<f32> {
+        let node_count = self.values.len();
+        self.batch_values.clear();
+        self.batch_values.resize(node_count * rows, 0.0);
+        self.batch_sums.clear();
+        self.batch_sums.resize(rows, 0.0);
+
+        // Transpose the rows so that every input node holds the values of all rows
+        if self.input_count > 0 {
+            for (row, row_inputs) in inputs.chunks(self.input_count).take(rows).enumerate() {
+                for (input, value) in row_inputs.iter().enumerate() {
+                    self.batch_values[input * rows + row] = *value;
+                }
+            }
+        }
+
+        let mut c = 0;
+        for node in self.input_count..self.computed_count {
+            for sum in self.batch_sums.iter_mut() {
+                *sum = 0.0;
+            }
+
+            while c < self.targets.len() && self.targets[c] == node {
+                let weight = self.weights[c];
+                let source = &self.batch_values[self.sources[c] * rows..(self.sources[c] + 1) * rows];
+                for (sum, value) in self.batch_sums.iter_mut().zip(source) {
+                    *sum += value * weight;
+                }
+                c += 1;
+            }
+
+            let target = &mut self.batch_values[node * rows..(node + 1) * rows];
+            for (value, sum) in target.iter_mut().zip(self.batch_sums.iter()) {
+                *value = sigmoid(*sum);
+            }
+        }
+
+        let mut outputs: Vec<f32> = vec![0.0; rows * self.outputs.len()];
+        for (output, index) in self.outputs.iter().enumerate() {
+            let values = &self.batch_values[index * rows..(index + 1) * rows];
+            for (row, value) in values.iter().enumerate() {
+                outputs[row * self.outputs.len() + output] = *value;
+            }
+        }
+
+        outputs
+    }
+
+    pub fn get_input_count(&self) -> usize {
+        self.input_count
+    }
+
+    pub fn get_output_count(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn get_node_count(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn get_connection_count(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// # get_computed_count
+    /// Nodes before this index are inputs or computed in order, the nodes after it always stay at zero
+    pub fn get_computed_count(&self) -> usize {
+        self.computed_count
+    }
+
+    /// # get_output_indices
+    /// Index of every output node in the node array
+    pub fn get_output_indices(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    /// # get_sources
+    /// Source node index of every connection, the connections are sorted by target
+    pub fn get_sources(&self) -> &[usize] {
+        &self.sources
+    }
+
+    pub fn get_targets(&self) -> &[usize] {
+        &self.targets
+    }
+
+    pub fn get_weights(&self) -> &[f32] {
+        &self.weights
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::Genome;
+use crate::NodeGeneType;
+
+pub mod compiled;
+use compiled::CompiledNetwork;
+
+/// # Network
+/// The phenotype of a genome, a feed-forward network built from its expressed connections.
+///
+/// Inputs are fed to the INPUT nodes in ascending id order,
+/// and outputs are read from the OUTPUT nodes in ascending id order.
+#[derive(Debug, Clone)]
+pub struct Network {
+    inputs: Vec<i32>,
+    outputs: Vec<i32>,
+
+    // Hidden and output nodes, ordered so that every node comes after the nodes it depends on
+    order: Vec<i32>,
+    // Expressed connections going into each node, as (in node, weight)
+    incoming: HashMap<i32, Vec<(i32, f32)>>,
+
+    values: HashMap<i32, f32>,
+}
+
+impl Network {
+    pub fn from_genome(genome: &Genome) -> Network {
+        let nodes = genome.get_node_genes();
+
+        let mut inputs: Vec<i32> = Vec::new();
+        let mut outputs: Vec<i32> = Vec::new();
+        for node in nodes {
+            match node.get_type() {
+                NodeGeneType::INPUT => inputs.push(node.get_id()),
+                NodeGeneType::OUTPUT => outputs.push(node.get_id()),
+                NodeGeneType::HIDDEN => {}
+            }
+        }
+
+        let mut incoming: HashMap<i32, Vec<(i32, f32)>> = HashMap::new();
+        let mut outgoing: HashMap<i32, Vec<i32>> = HashMap::new();
+        for con in genome
+            .get_connection_genes()
+            .iter()
+            .filter(|con| con.is_expressed())
+        {
+            incoming
+                .entry(con.get_out_node())
+                .or_default()
+                .push((con.get_in_node(), con.get_weight()));
+            outgoing
+                .entry(con.get_in_node())
+                .or_default()
+                .push(con.get_out_node());
+        }
+
+        // Topological sort, nodes that are part of a cycle are never reached and stay at zero
+        let mut pending: HashMap<i32, usize> = nodes
+            .iter()
+            .map(|node| {
+                let id = node.get_id();
+                (id, incoming.get(&id).map_or(0, |v| v.len()))
+            })
+            .collect();
+        let mut ready: Vec<i32> = pending
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        ready.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut order: Vec<i32> = Vec::new();
+        while let Some(id) = ready.pop() {
+            if genome.get_node_gene(id).unwrap().get_type() != NodeGeneType::INPUT {
+                order.push(id);
+            }
+
+            if let Some(targets) = outgoing.get(&id) {
+                for target in targets {
+                    let count = pending.get_mut(target).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(*target);
+                    }
+                }
+            }
+        }
+
+        Network {
+            inputs,
+            outputs,
+            order,
+            incoming,
+            values: HashMap::new(),
+        }
+    }
+
+    /// # activate
+    /// Runs a single forward pass and returns the values of the output nodes
+    pub fn activate(&mut self, inputs: &[f32]) -> Vec<f32> {
+        self.values.clear();
+
+        for (id, value) in self.inputs.iter().zip(inputs.iter()) {
+            self.values.insert(*id, *value);
+        }
+
+        for id in &self.order {
+            let mut sum = 0.0;
+            if let Some(incoming) = self.incoming.get(id) {
+                for (in_node, weight) in incoming {
+                    sum += self.values.get(in_node).copied().unwrap_or(0.0) * weight;
+                }
+            }
+            self.values.insert(*id, sigmoid(sum));
+        }
+
+        self.outputs
+            .iter()
+            .map(|id| self.values.get(id).copied().unwrap_or(0.0))
+            .collect()
+    }
+
+    /// # compile
+    /// Flattens the network into a `CompiledNetwork` for fast inference
+    pub fn compile(&self) -> CompiledNetwork {
+        CompiledNetwork::from_network(self)
+    }
+
+    pub fn get_input_count(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn get_output_count(&self) -> usize {
+        self.outputs.len()
+    }
+}
+
+/// # sigmoid
+/// The steepened sigmoid used in the NEAT paper, 1 / (1 + e^(-4.9x))
+pub fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-4.9 * x).exp())
+}
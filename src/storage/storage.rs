@@ -0,0 +1,345 @@
+use std::fs;
+use std::io;
+
+use crate::Config;
+use crate::ConnectionGene;
+use crate::Counter;
+use crate::Evaluator;
+use crate::Genome;
+use crate::NodeGene;
+use crate::NodeGeneType;
+use crate::SearchPhase;
+
+fn invalid_data(line_number: usize, message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line_number + 1, message),
+    )
+}
+
+/// # genome_to_text
+/// Writes the mutation step size of the genome followed by one gene per line:
+///
+/// `mutation_step <step>`
+///
+/// `node <id> <INPUT|HIDDEN|OUTPUT>`
+///
+/// `connection <innovation> <in node> <out node> <weight> <expressed>`
+pub fn genome_to_text(genome: &Genome) -> String {
+    let mut text = format!("mutation_step {}\n", genome.get_mutation_step());
+
+    for node in genome.get_node_genes() {
+        text.push_str(&format!("node {} {:?}\n", node.get_id(), node.get_type()));
+    }
+
+    for con in genome.get_connection_genes() {
+        text.push_str(&format!(
+            "connection {} {} {} {} {}\n",
+            con.get_innovation(),
+            con.get_in_node(),
+            con.get_out_node(),
+            con.get_weight(),
+            con.is_expressed()
+        ));
+    }
+
+    text
+}
+
+/// # genome_from_text
+/// Reads a genome written by `genome_to_text`
+pub fn genome_from_text(text: &str) -> io::Result<Genome> {
+    genome_from_lines(text.lines().enumerate())
+}
+
+fn genome_from_lines<'a, I>(lines: I) -> io::Result<Genome>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    let mut genome = Genome::new();
+
+    for (line_number, line) in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+
+        let number = |index: usize| -> io::Result<i32> {
+            fields
+                .get(index)
+                .and_then(|field| field.parse::<i32>().ok())
+                .ok_or_else(|| invalid_data(line_number, format!("expected a number in '{}'", line)))
+        };
+
+        match fields[0] {
+            // Missing in genomes saved before step sizes existed, those keep the default
+            "mutation_step" => {
+                let step = fields
+                    .get(1)
+                    .and_then(|field| field.parse::<f32>().ok())
+                    .ok_or_else(|| invalid_data(line_number, format!("expected a step size in '{}'", line)))?;
+                genome.set_mutation_step(step);
+            }
+            "node" => {
+                let node_type = match fields.get(2) {
+                    Some(&"INPUT") => NodeGeneType::INPUT,
+                    Some(&"HIDDEN") => NodeGeneType::HIDDEN,
+                    Some(&"OUTPUT") => NodeGeneType::OUTPUT,
+                    _ => {
+                        return Err(invalid_data(
+                            line_number,
+                            format!("unknown node type in '{}'", line),
+                        ))
+                    }
+                };
+                genome.add_node_gene(NodeGene::new(node_type, number(1)?));
+            }
+            "connection" => {
+                let weight = fields
+                    .get(4)
+                    .and_then(|field| field.parse::<f32>().ok())
+                    .ok_or_else(|| invalid_data(line_number, format!("expected a weight in '{}'", line)))?;
+                let expressed = fields
+                    .get(5)
+                    .and_then(|field| field.parse::<bool>().ok())
+                    .ok_or_else(|| {
+                        invalid_data(line_number, format!("expected true or false in '{}'", line))
+                    })?;
+
+                genome.add_connection_gene(ConnectionGene::new(
+                    number(2)?,
+                    number(3)?,
+                    weight,
+                    expressed,
+                    number(1)?,
+                ));
+            }
+            _ => {
+                return Err(invalid_data(
+                    line_number,
+                    format!("expected a node or connection, got '{}'", line),
+                ))
+            }
+        }
+    }
+
+    Ok(genome)
+}
+
+pub fn save_genome(genome: &Genome, path: &str) -> io::Result<()> {
+    fs::write(path, genome_to_text(genome))
+}
+
+pub fn load_genome(path: &str) -> io::Result<Genome> {
+    genome_from_text(&fs::read_to_string(path)?)
+}
+
+/// # Checkpoint
+/// Everything needed to continue a run: the config, the population, the innovation counters
+/// and the state the evaluator tuned during the run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub config: Config,
+    pub generation: usize,
+    pub node_innovation: i32,
+    pub connection_innovation: i32,
+    // Description of the fitness provider, so a run can be resumed with the same task
+    pub task: String,
+    pub genomes: Vec<Genome>,
+    // Archived behaviours of novelty search, oldest first
+    pub novelty_archive: Vec<Vec<f32>>,
+    // Threshold the next generation is speciated with, changed by Config::dynamic_dt
+    pub compatibility_threshold: f32,
+    // Phased search state, see Evaluator::update_phase
+    pub phase: SearchPhase,
+    pub complexity_ceiling: f32,
+    pub lowest_complexity: f32,
+    pub generations_since_lowest: usize,
+}
+
+impl Checkpoint {
+    /// # new
+    /// A checkpoint of the evaluator as it is now, ready to breed its next generation
+    pub fn new(
+        evaluator: &Evaluator,
+        node_innovation: &Counter,
+        connection_innovation: &Counter,
+        task: &str,
+    ) -> Checkpoint {
+        Checkpoint {
+            config: evaluator.get_config(),
+            generation: evaluator.get_generation(),
+            node_innovation: node_innovation.get_current_innovation(),
+            connection_innovation: connection_innovation.get_current_innovation(),
+            task: task.to_owned(),
+            genomes: evaluator.get_genomes().to_vec(),
+            novelty_archive: evaluator.get_novelty_archive().get_behaviours().to_vec(),
+            compatibility_threshold: evaluator.get_compatibility_threshold(),
+            phase: evaluator.get_phase(),
+            complexity_ceiling: evaluator.get_complexity_ceiling(),
+            lowest_complexity: evaluator.get_lowest_complexity(),
+            generations_since_lowest: evaluator.get_generations_since_lowest(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut text = String::new();
+
+        text.push_str("[checkpoint]\n");
+        text.push_str(&format!("generation = {}\n", self.generation));
+        text.push_str(&format!("node_innovation = {}\n", self.node_innovation));
+        text.push_str(&format!("connection_innovation = {}\n", self.connection_innovation));
+        text.push_str(&format!("task = {}\n", self.task));
+        text.push_str(&format!("compatibility_threshold = {}\n", self.compatibility_threshold));
+        text.push_str(&format!("phase = {}\n", self.phase.get_name()));
+        text.push_str(&format!("complexity_ceiling = {}\n", self.complexity_ceiling));
+        text.push_str(&format!("lowest_complexity = {}\n", self.lowest_complexity));
+        text.push_str(&format!("generations_since_lowest = {}\n", self.generations_since_lowest));
+
+        text.push_str("[config]\n");
+        text.push_str(&self.config.to_text());
+
+        for genome in &self.genomes {
+            text.push_str("[genome]\n");
+            text.push_str(&genome_to_text(genome));
+        }
+
+        // One `behaviour <value> <value> ...` line per archived behaviour
+        text.push_str("[novelty]\n");
+        for behaviour in &self.novelty_archive {
+            let values: Vec<String> = behaviour.iter().map(|value| value.to_string()).collect();
+            text.push_str(&format!("behaviour {}\n", values.join(" ")));
+        }
+
+        fs::write(path, text)
+    }
+
+    pub fn load(path: &str) -> io::Result<Checkpoint> {
+        let text = fs::read_to_string(path)?;
+
+        // Split the file into its sections, keeping the line numbers for error messages
+        let mut sections: Vec<(&str, Vec<(usize, &str)>)> = Vec::new();
+        for (line_number, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                sections.push((trimmed, Vec::new()));
+            } else if let Some((_, lines)) = sections.last_mut() {
+                lines.push((line_number, line));
+            } else if !trimmed.is_empty() {
+                return Err(invalid_data(line_number, String::from("expected a [section]")));
+            }
+        }
+
+        let mut checkpoint = Checkpoint {
+            config: Config::new(0, 0),
+            generation: 0,
+            node_innovation: 0,
+            connection_innovation: 0,
+            task: String::new(),
+            genomes: Vec::new(),
+            novelty_archive: Vec::new(),
+            compatibility_threshold: f32::NAN,
+            phase: SearchPhase::Complexifying,
+            complexity_ceiling: f32::MAX,
+            lowest_complexity: f32::MAX,
+            generations_since_lowest: 0,
+        };
+
+        for (name, lines) in sections {
+            match name {
+                "[checkpoint]" => {
+                    for (line_number, line) in lines {
+                        let mut parts = line.splitn(2, '=');
+                        let key = parts.next().unwrap_or("").trim();
+                        let value = parts.next().unwrap_or("").trim();
+                        let number = || {
+                            value.parse::<i32>().map_err(|_| {
+                                invalid_data(line_number, format!("'{}' is not a number", value))
+                            })
+                        };
+                        let float = || {
+                            value.parse::<f32>().map_err(|_| {
+                                invalid_data(line_number, format!("'{}' is not a number", value))
+                            })
+                        };
+
+                        match key {
+                            "generation" => {
+                                checkpoint.generation = value.parse::<usize>().map_err(|_| {
+                                    invalid_data(
+                                        line_number,
+                                        format!("'{}' is not a generation number", value),
+                                    )
+                                })?
+                            }
+                            "node_innovation" => checkpoint.node_innovation = number()?,
+                            "connection_innovation" => checkpoint.connection_innovation = number()?,
+                            "task" => checkpoint.task = value.to_owned(),
+                            "compatibility_threshold" => checkpoint.compatibility_threshold = float()?,
+                            "phase" => {
+                                checkpoint.phase = SearchPhase::from_name(value).ok_or_else(|| {
+                                    invalid_data(line_number, format!("unknown phase '{}'", value))
+                                })?
+                            }
+                            "complexity_ceiling" => checkpoint.complexity_ceiling = float()?,
+                            "lowest_complexity" => checkpoint.lowest_complexity = float()?,
+                            "generations_since_lowest" => {
+                                checkpoint.generations_since_lowest = value.parse::<usize>().map_err(|_| {
+                                    invalid_data(line_number, format!("'{}' is not a generation count", value))
+                                })?
+                            }
+                            "" => {}
+                            _ => {
+                                return Err(invalid_data(line_number, format!("unknown key '{}'", key)))
+                            }
+                        }
+                    }
+                }
+                "[config]" => {
+                    let config_text: Vec<&str> = lines.iter().map(|(_, line)| *line).collect();
+                    checkpoint.config = Config::from_text(&config_text.join("\n"))?;
+                }
+                "[genome]" => {
+                    checkpoint.genomes.push(genome_from_lines(lines.into_iter())?);
+                }
+                "[novelty]" => {
+                    for (line_number, line) in lines {
+                        let fields: Vec<&str> = line.split_whitespace().collect();
+                        match fields.first() {
+                            Some(&"behaviour") => {
+                                let behaviour = fields[1..]
+                                    .iter()
+                                    .map(|field| field.parse::<f32>())
+                                    .collect::<Result<Vec<f32>, _>>()
+                                    .map_err(|_| {
+                                        invalid_data(line_number, format!("expected numbers in '{}'", line))
+                                    })?;
+                                checkpoint.novelty_archive.push(behaviour);
+                            }
+                            None => {}
+                            _ => {
+                                return Err(invalid_data(
+                                    line_number,
+                                    format!("expected a behaviour, got '{}'", line),
+                                ))
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown section {}", name),
+                    ))
+                }
+            }
+        }
+
+        // Checkpoints saved before the threshold was stored start from the configured one
+        if checkpoint.compatibility_threshold.is_nan() {
+            checkpoint.compatibility_threshold = checkpoint.config.dt;
+        }
+
+        Ok(checkpoint)
+    }
+}
@@ -0,0 +1,307 @@
+use std::fs;
+use std::io;
+
+use crate::CompiledNetwork;
+use crate::FitnessGenomeProvider;
+use crate::Genome;
+
+use rand::seq::SliceRandom;
+
+/// # Metric
+/// How the outputs of a network are scored against the expected outputs of a dataset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    MeanSquaredError,
+    // Binary cross-entropy, averaged over every output of every row
+    CrossEntropy,
+    // With one output a row is correct when the output is on the right side of 0.5,
+    // with several outputs when the largest output is the expected class
+    Accuracy,
+}
+
+/// # Dataset
+/// Rows of numeric inputs and expected outputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dataset {
+    inputs: Vec<Vec<f32>>,
+    outputs: Vec<Vec<f32>>,
+}
+
+impl Dataset {
+    /// # new
+    /// A dataset of the rows `inputs[i]` and `outputs[i]`, panics when there isn't an output for every input
+    pub fn new(inputs: Vec<Vec<f32>>, outputs: Vec<Vec<f32>>) -> Self {
+        assert_eq!(
+            inputs.len(),
+            outputs.len(),
+            "a dataset needs as many output rows as input rows"
+        );
+
+        Self { inputs, outputs }
+    }
+
+    /// # from_csv
+    /// Loads a comma separated file, `input_columns` and `output_columns` are zero based column indices
+    pub fn from_csv(
+        path: &str,
+        input_columns: &[usize],
+        output_columns: &[usize],
+        has_header: bool,
+    ) -> io::Result<Dataset> {
+        let text = fs::read_to_string(path)?;
+
+        Dataset::from_csv_str(&text, input_columns, output_columns, has_header)
+    }
+
+    /// # from_csv_str
+    /// Same as `from_csv`, for csv text that is already in memory
+    pub fn from_csv_str(
+        text: &str,
+        input_columns: &[usize],
+        output_columns: &[usize],
+        has_header: bool,
+    ) -> io::Result<Dataset> {
+        let mut inputs: Vec<Vec<f32>> = Vec::new();
+        let mut outputs: Vec<Vec<f32>> = Vec::new();
+
+        let skip = if has_header { 1 } else { 0 };
+        for (line_number, line) in text.lines().enumerate().skip(skip) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // Only the selected columns are parsed, the others may hold anything, e.g. ids or labels
+            let fields: Vec<&str> = line.split(',').collect();
+            let select = |columns: &[usize]| -> io::Result<Vec<f32>> {
+                columns
+                    .iter()
+                    .map(|column| {
+                        let field = fields.get(*column).ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("line {}: column {} does not exist", line_number + 1, column),
+                            )
+                        })?;
+
+                        field.trim().parse::<f32>().map_err(|_| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("line {}: '{}' is not a number", line_number + 1, field.trim()),
+                            )
+                        })
+                    })
+                    .collect()
+            };
+
+            inputs.push(select(input_columns)?);
+            outputs.push(select(output_columns)?);
+        }
+
+        Ok(Dataset { inputs, outputs })
+    }
+
+    /// # split
+    /// Shuffles the rows and splits them into a training and a validation set,
+    /// `validation_fraction` of the rows end up in the validation set
+    pub fn split(&self, validation_fraction: f32) -> (Dataset, Dataset) {
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        indices.shuffle(&mut rand::thread_rng());
+
+        let validation_count = (self.len() as f32 * validation_fraction).round() as usize;
+        let (validation_indices, training_indices) = indices.split_at(validation_count.min(self.len()));
+
+        let subset = |indices: &[usize]| Dataset {
+            inputs: indices.iter().map(|i| self.inputs[*i].clone()).collect(),
+            outputs: indices.iter().map(|i| self.outputs[*i].clone()).collect(),
+        };
+
+        (subset(training_indices), subset(validation_indices))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn get_inputs(&self) -> &[Vec<f32>] {
+        &self.inputs
+    }
+
+    pub fn get_outputs(&self) -> &[Vec<f32>] {
+        &self.outputs
+    }
+}
+
+/// # DatasetTask
+/// Supervised learning on a dataset.
+///
+/// Every row is fed to the INPUT nodes followed by a bias of 1.0, and the OUTPUT nodes are compared to the
+/// expected outputs. Fitness is computed on the training set, higher is better for every metric:
+/// the error metrics are turned into 1 / (1 + error).
+pub struct DatasetTask {
+    training: Dataset,
+    validation: Dataset,
+    metric: Metric,
+}
+
+impl DatasetTask {
+    pub fn new(dataset: &Dataset, validation_fraction: f32, metric: Metric) -> Self {
+        let (training, validation) = dataset.split(validation_fraction);
+
+        Self {
+            training,
+            validation,
+            metric,
+        }
+    }
+
+    /// # get_input_count
+    /// Number of INPUT nodes a genome needs, including the bias
+    pub fn get_input_count(&self) -> usize {
+        self.training
+            .inputs
+            .first()
+            .or_else(|| self.validation.inputs.first())
+            .map_or(0, |row| row.len())
+            + 1
+    }
+
+    pub fn get_output_count(&self) -> usize {
+        self.training
+            .outputs
+            .first()
+            .or_else(|| self.validation.outputs.first())
+            .map_or(0, |row| row.len())
+    }
+
+    pub fn get_training(&self) -> &Dataset {
+        &self.training
+    }
+
+    pub fn get_validation(&self) -> &Dataset {
+        &self.validation
+    }
+
+    /// # validation_fitness
+    /// Fitness of the genome on the rows it was not trained on
+    pub fn validation_fitness(&self, genome: &Genome) -> f32 {
+        self.score(genome, &self.validation)
+    }
+
+    /// # score
+    /// Fitness of the genome on any dataset, using the metric of the task
+    pub fn score(&self, genome: &Genome, dataset: &Dataset) -> f32 {
+        let (predictions, output_count) = DatasetTask::predict(genome, dataset);
+
+        self.score_predictions(&predictions, output_count, dataset)
+    }
+
+    /// # predict
+    /// Outputs of the genome for every row of the dataset, one row after the other,
+    /// along with the number of outputs per row
+    fn predict(genome: &Genome, dataset: &Dataset) -> (Vec<f32>, usize) {
+        let mut network = CompiledNetwork::from_genome(genome);
+        let output_count = network.get_output_count();
+        if dataset.is_empty() {
+            return (Vec::new(), output_count);
+        }
+
+        // Every row followed by the bias, padded or cut to the inputs of the network, as one batch
+        let input_count = network.get_input_count();
+        let mut batch: Vec<f32> = vec![0.0; dataset.len() * input_count];
+        for (row, inputs) in batch.chunks_mut(input_count.max(1)).zip(dataset.inputs.iter()) {
+            for (value, input) in row.iter_mut().zip(inputs.iter().chain(&[1.0])) {
+                *value = *input;
+            }
+        }
+
+        (network.predict_batch(&batch, dataset.len()), output_count)
+    }
+
+    fn score_predictions(&self, predictions: &[f32], output_count: usize, dataset: &Dataset) -> f32 {
+        if dataset.is_empty() {
+            return 0.0;
+        }
+
+        let mut total = 0.0;
+        for (row, expected) in dataset.outputs.iter().enumerate() {
+            let outputs = &predictions[row * output_count..(row + 1) * output_count];
+
+            total += match self.metric {
+                Metric::MeanSquaredError => {
+                    outputs
+                        .iter()
+                        .zip(expected.iter())
+                        .map(|(o, e)| (o - e) * (o - e))
+                        .sum::<f32>()
+                        / expected.len().max(1) as f32
+                }
+                Metric::CrossEntropy => {
+                    outputs
+                        .iter()
+                        .zip(expected.iter())
+                        .map(|(o, e)| {
+                            let o = o.clamp(1e-7, 1.0 - 1e-7);
+                            -(e * o.ln() + (1.0 - e) * (1.0 - o).ln())
+                        })
+                        .sum::<f32>()
+                        / expected.len().max(1) as f32
+                }
+                Metric::Accuracy => {
+                    if DatasetTask::is_correct(outputs, expected) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                }
+            };
+        }
+
+        let mean = total / dataset.len() as f32;
+        match self.metric {
+            Metric::MeanSquaredError | Metric::CrossEntropy => 1.0 / (1.0 + mean),
+            Metric::Accuracy => mean,
+        }
+    }
+
+    fn is_correct(outputs: &[f32], expected: &[f32]) -> bool {
+        if expected.len() == 1 {
+            return outputs
+                .first()
+                .is_some_and(|output| (*output > 0.5) == (expected[0] > 0.5));
+        }
+
+        let arg_max = |values: &[f32]| {
+            values
+                .iter()
+                .enumerate()
+                .fold((0, f32::MIN), |best, (i, v)| if *v > best.1 { (i, *v) } else { best })
+                .0
+        };
+
+        arg_max(outputs) == arg_max(expected)
+    }
+}
+
+impl FitnessGenomeProvider for DatasetTask {
+    fn fitness_genome_evaluator(&self, genome: &Genome) -> f32 {
+        self.score(genome, &self.training)
+    }
+
+    // The outputs for every training row describe what a genome does
+    fn fitness_and_behaviour(&self, genome: &Genome) -> (f32, Vec<f32>) {
+        let (predictions, output_count) = DatasetTask::predict(genome, &self.training);
+
+        (
+            self.score_predictions(&predictions, output_count, &self.training),
+            predictions,
+        )
+    }
+
+    fn has_behaviour(&self) -> bool {
+        true
+    }
+}
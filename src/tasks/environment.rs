@@ -0,0 +1,98 @@
+use crate::CompiledNetwork;
+use crate::FitnessGenomeProvider;
+use crate::Genome;
+
+/// # Environment
+/// A control task that a network drives one step at a time
+pub trait Environment {
+    /// Puts the environment back in a starting state
+    fn reset(&mut self);
+
+    /// The current state, fed to the network as its inputs
+    fn observe(&self) -> Vec<f32>;
+
+    /// Applies the outputs of the network, advances one step and returns the reward of that step
+    fn act(&mut self, outputs: &[f32]) -> f32;
+
+    /// Whether the episode has ended, regardless of the step limit
+    fn is_done(&self) -> bool;
+}
+
+/// # run_episode
+/// Resets the environment and lets the network control it until it is done or `max_steps` have passed,
+/// returns the summed reward of the episode and the number of steps it lasted
+pub fn run_episode<E: Environment>(
+    network: &mut CompiledNetwork,
+    environment: &mut E,
+    max_steps: usize,
+) -> (f32, usize) {
+    environment.reset();
+
+    let mut reward = 0.0;
+    let mut steps = 0;
+    while !environment.is_done() && steps < max_steps {
+        let outputs = network.activate(&environment.observe());
+        reward += environment.act(&outputs);
+        steps += 1;
+    }
+
+    (reward, steps)
+}
+
+/// # EpisodeRunner
+/// Scores a genome by the mean reward of its phenotype over a number of episodes of an environment.
+///
+/// Can be passed straight to `Evaluator::evaluate_generation`.
+pub struct EpisodeRunner<E: Environment + Clone> {
+    environment: E,
+    episodes: usize,
+    max_steps: usize,
+}
+
+impl<E: Environment + Clone> EpisodeRunner<E> {
+    pub fn new(environment: E, episodes: usize, max_steps: usize) -> Self {
+        Self {
+            environment,
+            episodes,
+            max_steps,
+        }
+    }
+
+    pub fn get_episodes(&self) -> usize {
+        self.episodes
+    }
+
+    pub fn get_max_steps(&self) -> usize {
+        self.max_steps
+    }
+}
+
+impl<E: Environment + Clone> FitnessGenomeProvider for EpisodeRunner<E> {
+    fn fitness_genome_evaluator(&self, genome: &Genome) -> f32 {
+        self.fitness_and_behaviour(genome).0
+    }
+
+    // The mean of the last observation of every episode describes what a genome does
+    fn fitness_and_behaviour(&self, genome: &Genome) -> (f32, Vec<f32>) {
+        let mut network = CompiledNetwork::from_genome(genome);
+        let mut environment = self.environment.clone();
+
+        let mut total_reward = 0.0;
+        let mut behaviour: Vec<f32> = Vec::new();
+        for _ in 0..self.episodes {
+            total_reward += run_episode(&mut network, &mut environment, self.max_steps).0;
+
+            let observation = environment.observe();
+            behaviour.resize(observation.len(), 0.0);
+            for (value, observed) in behaviour.iter_mut().zip(observation) {
+                *value += observed / self.episodes as f32;
+            }
+        }
+
+        (total_reward / self.episodes.max(1) as f32, behaviour)
+    }
+
+    fn has_behaviour(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,453 @@
+use std::collections::VecDeque;
+
+use super::environment::run_episode;
+use super::environment::Environment;
+use crate::CompiledNetwork;
+use crate::FitnessGenomeProvider;
+use crate::Genome;
+
+use rand::Rng;
+
+const GRAVITY: f64 = 9.8;
+const CART_MASS: f64 = 1.0;
+const FORCE_MAGNITUDE: f64 = 10.0;
+const TRACK_LIMIT: f64 = 2.4;
+
+/// # CartPole
+/// The classic single pole balancing task, with the physics used in the NEAT paper.
+///
+/// The network sees the cart position, cart velocity, pole angle, pole angular velocity and a bias,
+/// and pushes the cart right when its first output is above 0.5, otherwise left.
+#[derive(Debug, Clone)]
+pub struct CartPole {
+    x: f64,
+    x_dot: f64,
+    theta: f64,
+    theta_dot: f64,
+
+    steps: usize,
+}
+
+impl CartPole {
+    const POLE_MASS: f64 = 0.1;
+    // Half the length of the pole
+    const POLE_LENGTH: f64 = 0.5;
+    const TAU: f64 = 0.02;
+    const ANGLE_LIMIT: f64 = 12.0 * std::f64::consts::PI / 180.0;
+
+    pub fn new() -> Self {
+        let mut cart_pole = Self {
+            x: 0.0,
+            x_dot: 0.0,
+            theta: 0.0,
+            theta_dot: 0.0,
+            steps: 0,
+        };
+        cart_pole.reset();
+
+        cart_pole
+    }
+
+    /// # with_state
+    /// A cart pole starting from the given state instead of a random one
+    pub fn with_state(x: f64, x_dot: f64, theta: f64, theta_dot: f64) -> Self {
+        Self {
+            x,
+            x_dot,
+            theta,
+            theta_dot,
+            steps: 0,
+        }
+    }
+
+    /// # get_state
+    /// The cart position, cart velocity, pole angle and pole angular velocity
+    pub fn get_state(&self) -> [f64; 4] {
+        [self.x, self.x_dot, self.theta, self.theta_dot]
+    }
+
+    pub fn get_steps(&self) -> usize {
+        self.steps
+    }
+}
+
+impl Default for CartPole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Environment for CartPole {
+    /// # reset
+    /// Starts a new episode from a random state inside the safe bounds
+    fn reset(&mut self) {
+        let mut rng = rand::thread_rng();
+
+        self.x = rng.gen_range(-2.3, 2.3);
+        self.x_dot = rng.gen_range(-1.0, 1.0);
+        self.theta = rng.gen_range(-0.2, 0.2);
+        self.theta_dot = rng.gen_range(-1.5, 1.5);
+        self.steps = 0;
+    }
+
+    /// # observe
+    /// The state scaled to roughly [0, 1], followed by the bias
+    fn observe(&self) -> Vec<f32> {
+        vec![
+            ((self.x + TRACK_LIMIT) / (2.0 * TRACK_LIMIT)) as f32,
+            ((self.x_dot + 0.75) / 1.5) as f32,
+            ((self.theta + CartPole::ANGLE_LIMIT) / 0.41) as f32,
+            ((self.theta_dot + 1.0) / 2.0) as f32,
+            1.0,
+        ]
+    }
+
+    /// # act
+    /// Applies a bang-bang force from the first network output and advances the simulation one step,
+    /// every step the pole stays up is rewarded with 1.0
+    fn act(&mut self, outputs: &[f32]) -> f32 {
+        let force = if outputs[0] > 0.5 {
+            FORCE_MAGNITUDE
+        } else {
+            -FORCE_MAGNITUDE
+        };
+
+        let total_mass = CART_MASS + CartPole::POLE_MASS;
+        let pole_mass_length = CartPole::POLE_MASS * CartPole::POLE_LENGTH;
+        let cos_theta = self.theta.cos();
+        let sin_theta = self.theta.sin();
+
+        let temp = (force + pole_mass_length * self.theta_dot * self.theta_dot * sin_theta) / total_mass;
+        let theta_acc = (GRAVITY * sin_theta - cos_theta * temp)
+            / (CartPole::POLE_LENGTH
+                * (4.0 / 3.0 - CartPole::POLE_MASS * cos_theta * cos_theta / total_mass));
+        let x_acc = temp - pole_mass_length * theta_acc * cos_theta / total_mass;
+
+        // Euler integration
+        self.x += CartPole::TAU * self.x_dot;
+        self.x_dot += CartPole::TAU * x_acc;
+        self.theta += CartPole::TAU * self.theta_dot;
+        self.theta_dot += CartPole::TAU * theta_acc;
+
+        self.steps += 1;
+
+        if self.is_done() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// # is_done
+    /// The episode ends when the cart leaves the track or the pole falls past 12 degrees
+    fn is_done(&self) -> bool {
+        self.x.abs() > TRACK_LIMIT || self.theta.abs() > CartPole::ANGLE_LIMIT
+    }
+}
+
+/// # DoublePole
+/// Two poles of different lengths on the same cart, integrated with Runge-Kutta as in the NEAT paper.
+///
+/// With velocities the network sees all six state variables, without them only the cart position
+/// and the two pole angles. The first output is mapped to a continuous force in [-10, 10] N.
+#[derive(Debug, Clone)]
+pub struct DoublePole {
+    // x, x_dot, theta_1, theta_1_dot, theta_2, theta_2_dot
+    state: [f64; 6],
+    velocities: bool,
+
+    steps: usize,
+    // |x| + |x_dot| + |theta_1| + |theta_1_dot| of the last 100 steps, used by the Gruau fitness
+    jiggle: VecDeque<f64>,
+}
+
+impl DoublePole {
+    // Coefficient of friction of the poles' hinges
+    const MUP: f64 = 0.000002;
+    const POLE_MASS_1: f64 = 0.1;
+    const POLE_MASS_2: f64 = 0.01;
+    // Half the length of each pole
+    const POLE_LENGTH_1: f64 = 0.5;
+    const POLE_LENGTH_2: f64 = 0.05;
+    const TAU: f64 = 0.01;
+    const ANGLE_LIMIT: f64 = 36.0 * std::f64::consts::PI / 180.0;
+    const STARTING_ANGLE: f64 = 4.0156 * std::f64::consts::PI / 180.0;
+
+    pub fn new(velocities: bool) -> Self {
+        let mut double_pole = Self {
+            state: [0.0; 6],
+            velocities,
+            steps: 0,
+            jiggle: VecDeque::new(),
+        };
+        double_pole.reset();
+
+        double_pole
+    }
+
+    /// # with_state
+    /// A double pole starting from the given state instead of the tilted one,
+    /// in the order of `get_state`
+    pub fn with_state(state: [f64; 6], velocities: bool) -> Self {
+        Self {
+            state,
+            velocities,
+            steps: 0,
+            jiggle: VecDeque::new(),
+        }
+    }
+
+    /// # get_state
+    /// The cart position and velocity, followed by the angle and angular velocity of each pole
+    pub fn get_state(&self) -> [f64; 6] {
+        self.state
+    }
+
+    pub fn get_steps(&self) -> usize {
+        self.steps
+    }
+
+    /// # gruau_fitness
+    /// 0.1 * steps / 1000 + 0.9 * 0.75 / (sum of the jiggle over the last 100 steps),
+    /// the second term only counts once the poles have been balanced for 100 steps.
+    /// This rewards controllers that balance without wildly swinging the cart.
+    pub fn gruau_fitness(&self) -> f32 {
+        let f1 = self.steps as f64 / 1000.0;
+        let f2 = if self.steps < 100 {
+            0.0
+        } else {
+            let jiggle: f64 = self.jiggle.iter().sum();
+            0.75 / jiggle
+        };
+
+        (0.1 * f1 + 0.9 * f2) as f32
+    }
+
+    fn derivatives(&self, force: f64, state: &[f64; 6]) -> [f64; 6] {
+        let cos_theta_1 = state[2].cos();
+        let sin_theta_1 = state[2].sin();
+        let g_sin_theta_1 = -GRAVITY * sin_theta_1;
+        let cos_theta_2 = state[4].cos();
+        let sin_theta_2 = state[4].sin();
+        let g_sin_theta_2 = -GRAVITY * sin_theta_2;
+
+        let ml_1 = DoublePole::POLE_LENGTH_1 * DoublePole::POLE_MASS_1;
+        let ml_2 = DoublePole::POLE_LENGTH_2 * DoublePole::POLE_MASS_2;
+        let temp_1 = DoublePole::MUP * state[3] / ml_1;
+        let temp_2 = DoublePole::MUP * state[5] / ml_2;
+
+        // Effective forces and masses of the poles
+        let fi_1 = (ml_1 * state[3] * state[3] * sin_theta_1)
+            + (0.75 * DoublePole::POLE_MASS_1 * cos_theta_1 * (temp_1 + g_sin_theta_1));
+        let fi_2 = (ml_2 * state[5] * state[5] * sin_theta_2)
+            + (0.75 * DoublePole::POLE_MASS_2 * cos_theta_2 * (temp_2 + g_sin_theta_2));
+        let mi_1 = DoublePole::POLE_MASS_1 * (1.0 - (0.75 * cos_theta_1 * cos_theta_1));
+        let mi_2 = DoublePole::POLE_MASS_2 * (1.0 - (0.75 * cos_theta_2 * cos_theta_2));
+
+        let x_acc = (force + fi_1 + fi_2) / (mi_1 + mi_2 + CART_MASS);
+
+        [
+            state[1],
+            x_acc,
+            state[3],
+            -0.75 * (x_acc * cos_theta_1 + g_sin_theta_1 + temp_1) / DoublePole::POLE_LENGTH_1,
+            state[5],
+            -0.75 * (x_acc * cos_theta_2 + g_sin_theta_2 + temp_2) / DoublePole::POLE_LENGTH_2,
+        ]
+    }
+
+    fn runge_kutta(&mut self, action: f64) {
+        let force = (action - 0.5) * FORCE_MAGNITUDE * 2.0;
+        let tau = DoublePole::TAU;
+        let offset = |state: &[f64; 6], d: &[f64; 6], h: f64| {
+            let mut next = *state;
+            for i in 0..6 {
+                next[i] += h * d[i];
+            }
+            next
+        };
+
+        let k1 = self.derivatives(force, &self.state);
+        let k2 = self.derivatives(force, &offset(&self.state, &k1, tau / 2.0));
+        let k3 = self.derivatives(force, &offset(&self.state, &k2, tau / 2.0));
+        let k4 = self.derivatives(force, &offset(&self.state, &k3, tau));
+
+        for i in 0..6 {
+            self.state[i] += tau / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+        }
+    }
+}
+
+impl Environment for DoublePole {
+    /// # reset
+    /// Starts a new episode with the long pole tilted by 4 degrees (0.07 rad), as in the paper
+    fn reset(&mut self) {
+        self.state = [0.0, 0.0, DoublePole::STARTING_ANGLE, 0.0, 0.0, 0.0];
+        self.steps = 0;
+        self.jiggle.clear();
+    }
+
+    /// # observe
+    /// The scaled state followed by the bias, velocities are left out when the task hides them
+    fn observe(&self) -> Vec<f32> {
+        let s = &self.state;
+        if self.velocities {
+            vec![
+                (s[0] / 4.8) as f32,
+                (s[1] / 2.0) as f32,
+                (s[2] / 0.52) as f32,
+                (s[3] / 2.0) as f32,
+                (s[4] / 0.52) as f32,
+                (s[5] / 2.0) as f32,
+                1.0,
+            ]
+        } else {
+            vec![
+                (s[0] / 4.8) as f32,
+                (s[2] / 0.52) as f32,
+                (s[4] / 0.52) as f32,
+                1.0,
+            ]
+        }
+    }
+
+    /// # act
+    /// Applies the force of the first network output for two integration steps of 0.01 s,
+    /// every step the poles stay up is rewarded with 1.0
+    fn act(&mut self, outputs: &[f32]) -> f32 {
+        let action = f64::from(outputs[0]);
+
+        for _ in 0..2 {
+            self.runge_kutta(action);
+        }
+
+        let s = &self.state;
+        self.jiggle
+            .push_back(s[0].abs() + s[1].abs() + s[2].abs() + s[3].abs());
+        if self.jiggle.len() > 100 {
+            self.jiggle.pop_front();
+        }
+
+        self.steps += 1;
+
+        if self.is_done() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// # is_done
+    /// The episode ends when the cart leaves the track or either pole falls past 36 degrees
+    fn is_done(&self) -> bool {
+        self.state[0].abs() > TRACK_LIMIT
+            || self.state[2].abs() > DoublePole::ANGLE_LIMIT
+            || self.state[4].abs() > DoublePole::ANGLE_LIMIT
+    }
+}
+
+/// # PoleBalancing
+/// The variants of the pole balancing benchmark
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoleBalancing {
+    SinglePole,
+    DoublePole,
+    // Double pole without velocity inputs, scored with the Gruau fitness
+    DoublePoleNoVelocities,
+}
+
+/// # PoleBalancingTask
+/// Runs one episode of the chosen variant per genome.
+///
+/// The fitness is the number of steps the poles stayed up, except for the variant without velocities
+/// which uses the Gruau fitness over at most 1000 steps.
+pub struct PoleBalancingTask {
+    variant: PoleBalancing,
+    max_steps: usize,
+}
+
+impl PoleBalancingTask {
+    pub fn new(variant: PoleBalancing, max_steps: usize) -> Self {
+        Self { variant, max_steps }
+    }
+
+    /// # get_input_count
+    /// Number of INPUT nodes a genome needs for the variant, including the bias
+    pub fn get_input_count(&self) -> usize {
+        match self.variant {
+            PoleBalancing::SinglePole => 5,
+            PoleBalancing::DoublePole => 7,
+            PoleBalancing::DoublePoleNoVelocities => 4,
+        }
+    }
+
+    pub fn get_output_count(&self) -> usize {
+        1
+    }
+
+    /// # balanced_steps
+    /// Number of steps the genome keeps the poles up, up to `max_steps`
+    pub fn balanced_steps(&self, genome: &Genome) -> usize {
+        let mut network = CompiledNetwork::from_genome(genome);
+
+        // Counted from the steps, the step the poles fall on earns no reward
+        let (_, steps) = match self.variant {
+            PoleBalancing::SinglePole => {
+                run_episode(&mut network, &mut CartPole::new(), self.max_steps)
+            }
+            PoleBalancing::DoublePole => {
+                run_episode(&mut network, &mut DoublePole::new(true), self.max_steps)
+            }
+            PoleBalancing::DoublePoleNoVelocities => {
+                run_episode(&mut network, &mut DoublePole::new(false), self.max_steps)
+            }
+        };
+
+        steps
+    }
+
+    /// # is_solved
+    /// The task is solved when the poles are kept up for all `max_steps` steps
+    pub fn is_solved(&self, genome: &Genome) -> bool {
+        self.balanced_steps(genome) >= self.max_steps
+    }
+}
+
+impl FitnessGenomeProvider for PoleBalancingTask {
+    fn fitness_genome_evaluator(&self, genome: &Genome) -> f32 {
+        self.fitness_and_behaviour(genome).0
+    }
+
+    // The state the cart and poles end up in describes what a genome does
+    fn fitness_and_behaviour(&self, genome: &Genome) -> (f32, Vec<f32>) {
+        let mut network = CompiledNetwork::from_genome(genome);
+        let behaviour = |state: &[f64]| state.iter().map(|value| *value as f32).collect();
+
+        match self.variant {
+            PoleBalancing::SinglePole => {
+                let mut cart_pole = CartPole::new();
+                let (_, steps) = run_episode(&mut network, &mut cart_pole, self.max_steps);
+
+                (steps as f32, behaviour(&cart_pole.get_state()))
+            }
+            PoleBalancing::DoublePole => {
+                let mut double_pole = DoublePole::new(true);
+                let (_, steps) = run_episode(&mut network, &mut double_pole, self.max_steps);
+
+                (steps as f32, behaviour(&double_pole.get_state()))
+            }
+            PoleBalancing::DoublePoleNoVelocities => {
+                let mut double_pole = DoublePole::new(false);
+
+                // The Gruau fitness is defined over 1000 steps
+                run_episode(&mut network, &mut double_pole, self.max_steps.min(1000));
+
+                (double_pole.gruau_fitness(), behaviour(&double_pole.get_state()))
+            }
+        }
+    }
+
+    fn has_behaviour(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,4 @@
+pub mod dataset;
+pub mod environment;
+pub mod pole_balancing;
+pub mod xor;
@@ -0,0 +1,85 @@
+use crate::CompiledNetwork;
+use crate::Counter;
+use crate::FitnessGenomeProvider;
+use crate::Genome;
+
+/// Inputs of the four XOR cases, the last input of each case is the bias and is always 1.0
+pub const XOR_INPUTS: [[f32; 3]; 4] = [
+    [0.0, 0.0, 1.0],
+    [0.0, 1.0, 1.0],
+    [1.0, 0.0, 1.0],
+    [1.0, 1.0, 1.0],
+];
+
+/// Expected output of each case in `XOR_INPUTS`
+pub const XOR_OUTPUTS: [f32; 4] = [0.0, 1.0, 1.0, 0.0];
+
+/// # XorTask
+/// The XOR benchmark from the NEAT paper.
+///
+/// The fitness is 4 minus the summed distance between the outputs and the expected outputs, squared,
+/// so a perfect network scores `XorTask::MAX_FITNESS`.
+#[derive(Default)]
+pub struct XorTask {}
+
+impl XorTask {
+    pub const INPUT_COUNT: usize = 3;
+    pub const OUTPUT_COUNT: usize = 1;
+    pub const MAX_FITNESS: f32 = 16.0;
+
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// # starting_genome
+    /// The minimal genome for the task, two inputs and a bias connected to one output
+    pub fn starting_genome(node_innovation: &mut Counter, connection_innovation: &mut Counter) -> Genome {
+        Genome::fully_connected(
+            XorTask::INPUT_COUNT,
+            XorTask::OUTPUT_COUNT,
+            node_innovation,
+            connection_innovation,
+        )
+    }
+
+    /// # outputs
+    /// Output of the genome for each of the four cases
+    pub fn outputs(genome: &Genome) -> Vec<f32> {
+        let mut network = CompiledNetwork::from_genome(genome);
+
+        XOR_INPUTS
+            .iter()
+            .map(|inputs| network.activate(inputs)[0])
+            .collect()
+    }
+
+    /// # is_solved
+    /// A genome solves XOR when every case is on the right side of 0.5
+    pub fn is_solved(genome: &Genome) -> bool {
+        XorTask::outputs(genome)
+            .iter()
+            .zip(XOR_OUTPUTS.iter())
+            .all(|(output, expected)| (output - expected).abs() < 0.5)
+    }
+}
+
+impl FitnessGenomeProvider for XorTask {
+    fn fitness_genome_evaluator(&self, genome: &Genome) -> f32 {
+        let distance: f32 = XorTask::outputs(genome)
+            .iter()
+            .zip(XOR_OUTPUTS.iter())
+            .map(|(output, expected)| (output - expected).abs())
+            .sum();
+
+        (4.0 - distance).powi(2)
+    }
+
+    // The outputs of the four cases describe what a genome does
+    fn fitness_and_behaviour(&self, genome: &Genome) -> (f32, Vec<f32>) {
+        (self.fitness_genome_evaluator(genome), XorTask::outputs(genome))
+    }
+
+    fn has_behaviour(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,77 @@
+//! Fixtures shared by the integration tests, not every test uses all of them
+#![allow(dead_code)]
+
+use neat_java::Config;
+use neat_java::Counter;
+use neat_java::Evaluator;
+use neat_java::FitnessGenomeProvider;
+use neat_java::GenesisGenomeProvider;
+use neat_java::Genome;
+
+use rand::Rng;
+
+/// Gives every genesis genome uniform random weights in [-1, 1]
+pub struct RandomWeights {}
+
+impl GenesisGenomeProvider for RandomWeights {
+    fn generate_genesis_genome(&self, genome: &Genome) -> Genome {
+        let mut rng = rand::thread_rng();
+        let mut genome = genome.clone();
+        for con in genome.get_connection_genes_mut() {
+            con.set_weight(rng.gen_range(-1.0, 1.0));
+        }
+
+        genome
+    }
+}
+
+/// Rewards the sum of the weights, so every genome has its own fitness
+pub struct WeightSum {}
+
+impl FitnessGenomeProvider for WeightSum {
+    fn fitness_genome_evaluator(&self, genome: &Genome) -> f32 {
+        genome
+            .get_connection_genes()
+            .iter()
+            .map(|con| con.get_weight())
+            .sum()
+    }
+}
+
+/// # evolve
+/// An evaluator started from `starting_genome` through `genesis`, that has evaluated `generations` generations of `task`
+pub fn evolve(
+    config: &Config,
+    starting_genome: fn(&mut Counter, &mut Counter) -> Genome,
+    genesis: Box<dyn GenesisGenomeProvider>,
+    task: fn() -> Box<dyn FitnessGenomeProvider>,
+    generations: usize,
+) -> Evaluator {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = starting_genome(&mut node_innovation, &mut connection_innovation);
+
+    let mut evaluator = Evaluator::new();
+    evaluator.init(config, &genome, genesis);
+    for _ in 0..generations {
+        evaluator
+            .evaluate_generation(task(), &mut node_innovation, &mut connection_innovation)
+            .unwrap();
+    }
+
+    evaluator
+}
+
+/// # evaluated
+/// `evolve` from a fully connected genome with 3 inputs and 1 output and random weights, scored by `WeightSum`
+pub fn evaluated(config: &Config, generations: usize) -> Evaluator {
+    evolve(
+        config,
+        |node_innovation, connection_innovation| {
+            Genome::fully_connected(3, 1, node_innovation, connection_innovation)
+        },
+        Box::new(RandomWeights {}),
+        || Box::new(WeightSum {}),
+        generations,
+    )
+}
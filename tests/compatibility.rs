@@ -0,0 +1,98 @@
+use neat_java::ConnectionGene;
+use neat_java::GeneComparison;
+use neat_java::Genome;
+
+/// A genome holding only connection genes with the given (innovation, weight) pairs
+fn genome_with_connections(connections: &[(i32, f32)]) -> Genome {
+    let mut genome = Genome::new();
+    for (innovation, weight) in connections {
+        genome.add_connection_gene(ConnectionGene::new(1, 2, *weight, true, *innovation));
+    }
+
+    genome
+}
+
+/// The parents of figure 4 in the NEAT paper: genes 6 to 8 are disjoint and 9 and 10 are excess
+fn paper_parents() -> (Genome, Genome) {
+    let parent1 = genome_with_connections(&[
+        (1, 1.0),
+        (2, 1.0),
+        (3, 1.0),
+        (4, 1.0),
+        (5, 1.0),
+        (8, 1.0),
+    ]);
+    let parent2 = genome_with_connections(&[
+        (1, 0.5),
+        (2, 1.0),
+        (3, 1.5),
+        (4, 1.0),
+        (5, 1.0),
+        (6, 1.0),
+        (7, 1.0),
+        (9, 1.0),
+        (10, 1.0),
+    ]);
+
+    (parent1, parent2)
+}
+
+#[test]
+fn paper_example_gene_counts() {
+    let (parent1, parent2) = paper_parents();
+
+    let expected = GeneComparison {
+        matching: 5,
+        disjoint: 3,
+        excess: 2,
+        // |1 - 0.5| + |1 - 1.5| over 5 matching genes
+        mean_weight_difference: 0.2,
+    };
+    assert_eq!(Genome::compare_genes(&parent1, &parent2), expected);
+
+    // The comparison does not depend on the order of the genomes
+    assert_eq!(Genome::compare_genes(&parent2, &parent1), expected);
+}
+
+#[test]
+fn paper_example_distance_without_normalisation() {
+    let (parent1, parent2) = paper_parents();
+
+    // Both genomes are below the threshold, so N = 1: 1 * 2 + 1 * 3 + 0.4 * 0.2
+    let distance = Genome::compatibility_distance(&parent1, &parent2, 1.0, 1.0, 0.4, 20);
+    assert!((distance - 5.08).abs() < 1e-5);
+}
+
+#[test]
+fn paper_example_distance_with_normalisation() {
+    let (parent1, parent2) = paper_parents();
+
+    // The larger genome has 9 genes: (1 * 2 + 1 * 3) / 9 + 0.4 * 0.2
+    let distance = Genome::compatibility_distance(&parent1, &parent2, 1.0, 1.0, 0.4, 9);
+    assert!((distance - (5.0 / 9.0 + 0.08)).abs() < 1e-5);
+}
+
+#[test]
+fn identical_genomes_have_zero_distance() {
+    let (parent1, _) = paper_parents();
+
+    assert_eq!(Genome::compatibility_distance(&parent1, &parent1, 1.0, 1.0, 0.4, 20), 0.0);
+}
+
+#[test]
+fn genomes_without_matching_genes() {
+    let genome1 = genome_with_connections(&[(1, 1.0), (2, 1.0)]);
+    let genome2 = genome_with_connections(&[(3, 1.0)]);
+    let empty = Genome::new();
+
+    let comparison = Genome::compare_genes(&genome1, &genome2);
+    assert_eq!(comparison.matching, 0);
+    assert_eq!(comparison.disjoint, 2);
+    assert_eq!(comparison.excess, 1);
+    assert_eq!(comparison.mean_weight_difference, 0.0);
+
+    // Every gene of a genome compared to an empty one is excess
+    let comparison = Genome::compare_genes(&genome1, &empty);
+    assert_eq!(comparison.disjoint, 0);
+    assert_eq!(comparison.excess, 2);
+}
@@ -0,0 +1,153 @@
+use neat_java::ArchiveInsertion;
+use neat_java::Config;
+use neat_java::CrossoverMethod;
+use neat_java::SelectionMethod;
+use neat_java::WeightMutation;
+
+/// A config with every field away from its default
+fn changed_config() -> Config {
+    let mut config = Config::new(123, 456);
+
+    config.c1 = 1.5;
+    config.c2 = 2.5;
+    config.c3 = 0.75;
+    config.compatibility_normalisation_threshold = 7;
+    config.dt = 4.5;
+
+    config.dynamic_dt = true;
+    config.target_species_count = 7;
+    config.dt_step = 0.2;
+    config.dt_min = 0.5;
+    config.dt_max = 12.0;
+
+    config.a_sexual_reproduction_rate = 0.5;
+    config.mutation_rate = 0.6;
+    config.pertrubing_rate = 0.7;
+
+    config.weight_mutation = WeightMutation::OneGene;
+    config.weight_mutation_gene_rate = 0.4;
+    config.weight_perturbation_sigma = 0.25;
+    config.weight_replace_range = 3.0;
+    config.weight_min = -5.0;
+    config.weight_max = 6.0;
+
+    config.self_adaptive_mutation = true;
+    config.mutation_step_min = 0.05;
+    config.mutation_step_max = 2.0;
+
+    config.disabled_gene_inheriting_chance = 0.45;
+
+    config.crossover = CrossoverMethod::MultiPoint;
+    config.crossover_points = 4;
+    config.crossover_equal_fitness_inherits_both = true;
+
+    config.add_connection_rate = 0.15;
+    config.add_node_rate = 0.06;
+    config.toggle_enable_rate = 0.02;
+    config.delete_connection_rate = 0.04;
+    config.delete_node_rate = 0.01;
+    config.offspring_from_crossover = 0.5;
+    config.interspecies_mating_rate = 0.25;
+    config.species_stagnation_generations = 20;
+
+    config.elitism_count = 3;
+    config.species_elitism = true;
+    config.species_elitism_min_size = 8;
+
+    config.selection = SelectionMethod::Tournament;
+    config.survival_fraction = 0.25;
+    config.tournament_size = 5;
+
+    config.novelty_search = true;
+    config.novelty_k = 7;
+    config.novelty_fitness_blend = 0.5;
+    config.novelty_archive_insertion = ArchiveInsertion::Random;
+    config.novelty_archive_threshold = 2.5;
+    config.novelty_archive_probability = 0.05;
+    config.novelty_archive_per_generation = 3;
+    config.novelty_archive_max_size = 50;
+
+    config.phased_search = true;
+    config.phased_search_complexity_threshold = 12.0;
+    config.phased_search_stall_generations = 4;
+
+    config
+}
+
+#[test]
+fn every_field_round_trips() {
+    let config = changed_config();
+
+    // Every written line differs from the default, so no field was left out above
+    let default_text = Config::new(0, 0).to_text();
+    let text = config.to_text();
+    assert_eq!(text.lines().count(), default_text.lines().count());
+    for (line, default_line) in text.lines().zip(default_text.lines()) {
+        assert_ne!(line, default_line);
+    }
+
+    let read = Config::from_text(&text).unwrap();
+    assert_eq!(read, config);
+    assert_eq!(read.get_population_size(), 123);
+    assert_eq!(read.get_generation_count(), 456);
+}
+
+#[test]
+fn names_round_trip() {
+    for method in &[
+        CrossoverMethod::Uniform,
+        CrossoverMethod::Averaging,
+        CrossoverMethod::SinglePoint,
+        CrossoverMethod::MultiPoint,
+    ] {
+        assert_eq!(CrossoverMethod::from_name(method.get_name()), Some(*method));
+    }
+    for method in &[
+        SelectionMethod::Truncation,
+        SelectionMethod::Tournament,
+        SelectionMethod::Roulette,
+        SelectionMethod::StochasticUniversalSampling,
+        SelectionMethod::Rank,
+    ] {
+        assert_eq!(SelectionMethod::from_name(method.get_name()), Some(*method));
+    }
+    for insertion in &[
+        ArchiveInsertion::Threshold,
+        ArchiveInsertion::Random,
+        ArchiveInsertion::MostNovel,
+    ] {
+        assert_eq!(
+            ArchiveInsertion::from_name(insertion.get_name()),
+            Some(*insertion)
+        );
+    }
+    for mutation in &[
+        WeightMutation::Gaussian,
+        WeightMutation::Multiplicative,
+        WeightMutation::OneGene,
+    ] {
+        assert_eq!(
+            WeightMutation::from_name(mutation.get_name()),
+            Some(*mutation)
+        );
+    }
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    assert!(Config::from_text("crossover = two-point").is_err());
+    assert!(Config::from_text("selection = best").is_err());
+    assert!(Config::from_text("novelty_archive_insertion = all").is_err());
+    assert!(Config::from_text("weight_mutation = uniform").is_err());
+    assert!(Config::from_text("species_elitism = yes").is_err());
+    assert!(Config::from_text("elitism_count = -1").is_err());
+    assert!(Config::from_text("unknown_key = 1").is_err());
+
+    assert!(Config::from_text("survival_fraction = 0").is_err());
+    assert!(Config::from_text("dt_min = 5\ndt_max = 1").is_err());
+    assert!(Config::from_text("weight_min = 2\nweight_max = 1").is_err());
+    assert!(Config::from_text("mutation_step_min = 2\nmutation_step_max = 1").is_err());
+    assert!(Config::from_text("weight_replace_range = 0").is_err());
+    assert!(Config::from_text("weight_replace_range = -1").is_err());
+    assert!(Config::from_text("weight_replace_range = inf").is_err());
+}
@@ -0,0 +1,111 @@
+use neat_java::Config;
+use neat_java::Counter;
+use neat_java::CrossoverMethod;
+use neat_java::Genome;
+
+/// Two copies of a fully connected genome, with all weights 1 and -1
+fn opposite_parents() -> (Genome, Genome) {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut parent1 = Genome::fully_connected(5, 2, &mut node_innovation, &mut connection_innovation);
+    let mut parent2 = parent1.clone();
+
+    for con in parent1.get_connection_genes_mut() {
+        con.set_weight(1.0);
+    }
+    for con in parent2.get_connection_genes_mut() {
+        con.set_weight(-1.0);
+    }
+
+    (parent1, parent2)
+}
+
+fn weights(genome: &Genome) -> Vec<f32> {
+    genome
+        .get_connection_genes()
+        .iter()
+        .map(|con| con.get_weight())
+        .collect()
+}
+
+fn innovations(genome: &Genome) -> Vec<i32> {
+    genome
+        .get_connection_genes()
+        .iter()
+        .map(|con| con.get_innovation())
+        .collect()
+}
+
+/// Number of times consecutive genes switch parent
+fn switches(weights: &[f32]) -> usize {
+    weights.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+#[test]
+fn averaging_crossover() {
+    let (parent1, parent2) = opposite_parents();
+    let mut config = Config::new(0, 0);
+    config.crossover = CrossoverMethod::Averaging;
+
+    let child = Genome::crossover_with(&parent1, &parent2, false, &config);
+    assert!(weights(&child).iter().all(|w| *w == 0.0));
+}
+
+#[test]
+fn point_crossover() {
+    let (parent1, parent2) = opposite_parents();
+    let mut config = Config::new(0, 0);
+
+    config.crossover = CrossoverMethod::SinglePoint;
+    for _ in 0..20 {
+        let child = Genome::crossover_with(&parent1, &parent2, false, &config);
+        let child_weights = weights(&child);
+        assert_eq!(child_weights[0], 1.0);
+        assert_eq!(switches(&child_weights), 1);
+    }
+
+    config.crossover = CrossoverMethod::MultiPoint;
+    config.crossover_points = 3;
+    for _ in 0..20 {
+        let child = Genome::crossover_with(&parent1, &parent2, false, &config);
+        let child_weights = weights(&child);
+        assert_eq!(child_weights[0], 1.0);
+        assert_eq!(switches(&child_weights), 3);
+    }
+}
+
+#[test]
+fn equal_fitness_inherits_both() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let base = Genome::fully_connected(3, 2, &mut node_innovation, &mut connection_innovation);
+
+    let mut config = Config::new(0, 0);
+    config.crossover_equal_fitness_inherits_both = true;
+
+    for _ in 0..20 {
+        // Splitting connections never closes a cycle, so the child can take every gene
+        let mut parent1 = base.clone();
+        let mut parent2 = base.clone();
+        for _ in 0..3 {
+            parent1.add_node_mutation(&mut connection_innovation, &mut node_innovation);
+            parent2.add_node_mutation(&mut connection_innovation, &mut node_innovation);
+        }
+
+        let mut both = innovations(&parent1);
+        both.extend(innovations(&parent2));
+        both.sort_unstable();
+        both.dedup();
+
+        let child = Genome::crossover_with(&parent1, &parent2, true, &config);
+        assert_eq!(innovations(&child), both);
+        for con in child.get_connection_genes() {
+            assert!(child.get_node_gene(con.get_in_node()).is_some());
+            assert!(child.get_node_gene(con.get_out_node()).is_some());
+        }
+
+        // A fitter parent_1 passes on only its own structure
+        let child = Genome::crossover_with(&parent1, &parent2, false, &config);
+        assert_eq!(innovations(&child), innovations(&parent1));
+    }
+}
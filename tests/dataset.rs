@@ -0,0 +1,146 @@
+use std::io;
+
+use neat_java::Counter;
+use neat_java::Dataset;
+use neat_java::DatasetTask;
+use neat_java::Genome;
+use neat_java::Metric;
+
+/// One input and the bias connected to a single output, with the given weights
+fn genome(input_weight: f32, bias_weight: f32) -> Genome {
+    let mut genome = Genome::fully_connected(2, 1, &mut Counter::new(), &mut Counter::new());
+    for (con, weight) in genome
+        .get_connection_genes_mut()
+        .iter_mut()
+        .zip(&[input_weight, bias_weight])
+    {
+        con.set_weight(*weight);
+    }
+
+    genome
+}
+
+fn dataset(rows: &[(f32, f32)]) -> Dataset {
+    Dataset::new(
+        rows.iter().map(|(input, _)| vec![*input]).collect(),
+        rows.iter().map(|(_, output)| vec![*output]).collect(),
+    )
+}
+
+#[test]
+fn csv_selects_columns() {
+    let text = "a,b,c\n1,2,3\n\n 4 , 5.5 ,-6\n";
+    let dataset = Dataset::from_csv_str(text, &[2, 0], &[1], true).unwrap();
+
+    assert_eq!(dataset.len(), 2);
+    assert_eq!(dataset.get_inputs(), &[vec![3.0, 1.0], vec![-6.0, 4.0]]);
+    assert_eq!(dataset.get_outputs(), &[vec![2.0], vec![5.5]]);
+
+    // Without a header the first line is a row
+    let dataset = Dataset::from_csv_str("1,2\n3,4", &[0], &[1], false).unwrap();
+    assert_eq!(dataset.get_inputs(), &[vec![1.0], vec![3.0]]);
+}
+
+#[test]
+fn malformed_csv_rows_are_rejected() {
+    let not_a_number = Dataset::from_csv_str("a,b\n1,2\n3,x\n", &[0], &[1], true).unwrap_err();
+    assert_eq!(not_a_number.kind(), io::ErrorKind::InvalidData);
+    assert!(not_a_number.to_string().contains("line 3"));
+    assert!(not_a_number.to_string().contains("'x'"));
+
+    let missing_column = Dataset::from_csv_str("1,2\n3\n", &[0], &[1], false).unwrap_err();
+    assert_eq!(missing_column.kind(), io::ErrorKind::InvalidData);
+    assert!(missing_column.to_string().contains("line 2"));
+    assert!(missing_column.to_string().contains("column 1"));
+
+    assert!(Dataset::from_csv_str("1,,2\n", &[1], &[2], false).is_err());
+}
+
+#[test]
+fn unselected_columns_may_hold_text() {
+    let text = "id,x,label,y\nfirst,1,cat,0\nsecond,2,,1\n";
+    let dataset = Dataset::from_csv_str(text, &[1], &[3], true).unwrap();
+
+    assert_eq!(dataset.get_inputs(), &[vec![1.0], vec![2.0]]);
+    assert_eq!(dataset.get_outputs(), &[vec![0.0], vec![1.0]]);
+}
+
+#[test]
+#[should_panic(expected = "as many output rows as input rows")]
+fn rows_without_outputs_are_rejected() {
+    Dataset::new(vec![vec![1.0], vec![2.0]], vec![vec![1.0]]);
+}
+
+#[test]
+fn split_keeps_every_row_once() {
+    let rows: Vec<(f32, f32)> = (0..10).map(|i| (i as f32, (i * 2) as f32)).collect();
+    let (training, validation) = dataset(&rows).split(0.3);
+
+    assert_eq!(training.len(), 7);
+    assert_eq!(validation.len(), 3);
+
+    let mut inputs: Vec<f32> = training
+        .get_inputs()
+        .iter()
+        .chain(validation.get_inputs())
+        .map(|row| row[0])
+        .collect();
+    inputs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(inputs, (0..10).map(|i| i as f32).collect::<Vec<f32>>());
+
+    // Outputs stay with their inputs
+    for (input, output) in training.get_inputs().iter().zip(training.get_outputs()) {
+        assert_eq!(output[0], input[0] * 2.0);
+    }
+
+    let (training, validation) = dataset(&rows).split(0.0);
+    assert_eq!((training.len(), validation.len()), (10, 0));
+}
+
+#[test]
+fn error_metrics() {
+    // With zero weights every output is 0.5
+    let genome = genome(0.0, 0.0);
+    let data = dataset(&[(1.0, 0.0), (2.0, 1.0), (3.0, 1.0), (4.0, 0.5)]);
+
+    // Squared errors 0.25, 0.25, 0.25 and 0, a mean of 0.1875
+    let task = DatasetTask::new(&data, 0.0, Metric::MeanSquaredError);
+    assert!((task.score(&genome, &data) - 1.0 / 1.1875).abs() < 1e-6);
+
+    // Every row loses ln(2) whatever its target
+    let task = DatasetTask::new(&data, 0.0, Metric::CrossEntropy);
+    assert!((task.score(&genome, &data) - 1.0 / (1.0 + 2f32.ln())).abs() < 1e-6);
+}
+
+#[test]
+fn accuracy_metric() {
+    // A steep positive weight outputs almost 1 for positive inputs and almost 0 for negative ones
+    let genome = genome(5.0, 0.0);
+    let data = dataset(&[(1.0, 1.0), (-1.0, 0.0), (2.0, 0.0), (-2.0, 1.0), (3.0, 1.0)]);
+
+    let task = DatasetTask::new(&data, 0.0, Metric::Accuracy);
+    assert!((task.score(&genome, &data) - 0.6).abs() < 1e-6);
+
+    // Several outputs pick the largest one as the class, ties go to the first
+    let mut genome = Genome::fully_connected(2, 2, &mut Counter::new(), &mut Counter::new());
+    for con in genome.get_connection_genes_mut() {
+        con.set_weight(0.0);
+    }
+    let data = Dataset::new(
+        vec![vec![1.0], vec![1.0], vec![1.0]],
+        vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.9, 0.1]],
+    );
+    let task = DatasetTask::new(&data, 0.0, Metric::Accuracy);
+    assert!((task.score(&genome, &data) - 2.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn empty_dataset_scores_zero() {
+    let data = dataset(&[(1.0, 1.0)]);
+    let task = DatasetTask::new(&data, 0.0, Metric::MeanSquaredError);
+
+    assert_eq!(
+        task.score(&genome(1.0, 1.0), &Dataset::new(Vec::new(), Vec::new())),
+        0.0
+    );
+}
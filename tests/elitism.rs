@@ -0,0 +1,66 @@
+mod common;
+
+use common::evaluated;
+use neat_java::Config;
+
+#[test]
+fn elites_are_copied_unchanged() {
+    let mut config = Config::new(30, 1);
+    config.elitism_count = 4;
+    // Every child is mutated, so only the elites can equal a genome of the last generation
+    config.mutation_rate = 1.0;
+    config.a_sexual_reproduction_rate = 0.0;
+
+    let evaluator = evaluated(&config, 1);
+
+    let results = evaluator.get_last_generation_results();
+    let genomes = evaluator.get_genomes();
+    assert_eq!(genomes.len(), 30);
+    for (elite, genome) in results.iter().zip(genomes).take(4) {
+        assert_eq!(elite.get_genome(), genome);
+    }
+}
+
+#[test]
+fn species_champions_are_copied() {
+    let mut config = Config::new(30, 1);
+    config.elitism_count = 0;
+    config.species_elitism = true;
+    config.species_elitism_min_size = 2;
+    // A threshold nothing reaches puts the whole population in one species
+    config.dt = f32::MAX;
+
+    let evaluator = evaluated(&config, 1);
+
+    let species = evaluator.get_species();
+    assert_eq!(species.len(), 1);
+    assert_eq!(species[0].get_members().len(), 30);
+    assert_eq!(species[0].get_champion(), Some(0));
+    assert_eq!(
+        &evaluator.get_genomes()[0],
+        evaluator.get_last_generation_results()[0].get_genome()
+    );
+}
+
+#[test]
+fn every_genome_belongs_to_one_species() {
+    let mut config = Config::new(40, 1);
+    config.dt = 0.05;
+
+    let evaluator = evaluated(&config, 1);
+
+    let mut members: Vec<usize> = evaluator
+        .get_species()
+        .iter()
+        .flat_map(|species| species.get_members().to_vec())
+        .collect();
+    members.sort_unstable();
+    assert_eq!(members, (0..40).collect::<Vec<usize>>());
+
+    // Random weights are far apart with a low threshold
+    assert!(evaluator.get_species().len() > 1);
+    assert_eq!(
+        evaluator.get_history()[0].species_count,
+        evaluator.get_species().len()
+    );
+}
@@ -0,0 +1,79 @@
+use neat_java::tasks::environment::run_episode;
+use neat_java::CompiledNetwork;
+use neat_java::Counter;
+use neat_java::Environment;
+use neat_java::EpisodeRunner;
+use neat_java::FitnessGenomeProvider;
+use neat_java::Genome;
+
+/// Ends after `length` steps, the last step earns no reward like a pole that falls
+#[derive(Clone)]
+struct Countdown {
+    length: usize,
+    steps: usize,
+}
+
+impl Environment for Countdown {
+    fn reset(&mut self) {
+        self.steps = 0;
+    }
+
+    fn observe(&self) -> Vec<f32> {
+        vec![1.0]
+    }
+
+    fn act(&mut self, _outputs: &[f32]) -> f32 {
+        self.steps += 1;
+        if self.is_done() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.steps >= self.length
+    }
+}
+
+fn genome() -> Genome {
+    Genome::fully_connected(1, 1, &mut Counter::new(), &mut Counter::new())
+}
+
+#[test]
+fn episode_counts_its_steps() {
+    let mut network = CompiledNetwork::from_genome(&genome());
+
+    // The failing step is counted even though it earns nothing
+    let mut environment = Countdown {
+        length: 7,
+        steps: 3,
+    };
+    assert_eq!(run_episode(&mut network, &mut environment, 100), (6.0, 7));
+
+    // The step limit cuts the episode short
+    assert_eq!(run_episode(&mut network, &mut environment, 5), (5.0, 5));
+}
+
+#[test]
+fn runner_scores_the_mean_reward() {
+    let runner = EpisodeRunner::new(
+        Countdown {
+            length: 10,
+            steps: 0,
+        },
+        3,
+        100,
+    );
+    assert_eq!(runner.fitness_genome_evaluator(&genome()), 9.0);
+
+    let runner = EpisodeRunner::new(
+        Countdown {
+            length: 10,
+            steps: 0,
+        },
+        3,
+        4,
+    );
+    assert_eq!(runner.fitness_genome_evaluator(&genome()), 4.0);
+}
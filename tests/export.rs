@@ -0,0 +1,280 @@
+use std::fs;
+use std::process::Command;
+
+use neat_java::export;
+use neat_java::CompiledNetwork;
+use neat_java::ConnectionGene;
+use neat_java::Counter;
+use neat_java::Genome;
+use neat_java::NodeGene;
+use neat_java::NodeGeneType;
+
+/// Two inputs (1, 2), a hidden node (4) and an output (3), with a disabled shortcut
+fn small_genome() -> Genome {
+    let mut genome = Genome::new();
+    genome.add_node_gene(NodeGene::new(NodeGeneType::INPUT, 1));
+    genome.add_node_gene(NodeGene::new(NodeGeneType::INPUT, 2));
+    genome.add_node_gene(NodeGene::new(NodeGeneType::OUTPUT, 3));
+    genome.add_node_gene(NodeGene::new(NodeGeneType::HIDDEN, 4));
+
+    genome.add_connection_gene(ConnectionGene::new(1, 3, 0.5, false, 1));
+    genome.add_connection_gene(ConnectionGene::new(1, 4, 1.0, true, 2));
+    genome.add_connection_gene(ConnectionGene::new(4, 3, -0.25, true, 3));
+    genome.add_connection_gene(ConnectionGene::new(2, 3, 2.0, true, 4));
+
+    genome
+}
+
+#[test]
+fn exports_rust() {
+    let expected = "/// Evolved network with 2 inputs and 1 outputs, generated by neat-java
+pub fn controller(inputs: &[f32; 2]) -> [f32; 1] {
+    fn sigmoid(x: f32) -> f32 {
+        1.0 / (1.0 + (-4.9 * x).exp())
+    }
+
+    let mut v = [0.0_f32; 4];
+    v[..2].copy_from_slice(inputs);
+    v[2] = sigmoid(0.0_f32 + v[0] * 1.0_f32);
+    v[3] = sigmoid(0.0_f32 + v[2] * -0.25_f32 + v[1] * 2.0_f32);
+
+    [v[3]]
+}
+";
+
+    assert_eq!(export::genome_to_rust(&small_genome(), "controller"), expected);
+}
+
+#[test]
+fn exports_c() {
+    let code = export::genome_to_c(&small_genome(), "my-controller");
+
+    assert!(code.contains("void my_controller(const float inputs[2], float outputs[1]) {"));
+    assert!(code.contains("    v[3] = my_controller_sigmoid(0.0f + v[2] * -0.25f + v[1] * 2.0f);"));
+    assert!(code.contains("    outputs[0] = v[3];"));
+}
+
+#[test]
+fn exports_non_finite_weights() {
+    let mut genome = small_genome();
+    genome
+        .get_connection_gene_mut(2)
+        .unwrap()
+        .set_weight(f32::NAN);
+    genome
+        .get_connection_gene_mut(3)
+        .unwrap()
+        .set_weight(f32::INFINITY);
+    genome
+        .get_connection_gene_mut(4)
+        .unwrap()
+        .set_weight(f32::NEG_INFINITY);
+
+    let rust = export::genome_to_rust(&genome, "controller");
+    assert!(rust.contains("v[2] = sigmoid(0.0_f32 + v[0] * f32::NAN);"));
+    assert!(
+        rust.contains("v[3] = sigmoid(0.0_f32 + v[2] * f32::INFINITY + v[1] * f32::NEG_INFINITY);")
+    );
+
+    let c = export::genome_to_c(&genome, "controller");
+    assert!(c.contains("v[2] = controller_sigmoid(0.0f + v[0] * NAN);"));
+    assert!(c.contains("v[3] = controller_sigmoid(0.0f + v[2] * INFINITY + v[1] * -INFINITY);"));
+}
+
+/// Compiles `code` with `compiler`, runs it and reads the output bits it prints, one line per row of inputs
+fn compile_and_run(
+    compiler: &str,
+    flags: &[&str],
+    file_name: &str,
+    code: &str,
+    name: &str,
+) -> Vec<Vec<f32>> {
+    let directory =
+        std::env::temp_dir().join(format!("neat-export-{}-{}", std::process::id(), name));
+    fs::create_dir_all(&directory).unwrap();
+    let source = directory.join(file_name);
+    let binary = directory.join("main");
+    fs::write(&source, code).unwrap();
+
+    let compiled = Command::new(compiler)
+        .arg("-o")
+        .arg(&binary)
+        .arg(&source)
+        .args(flags)
+        .output()
+        .unwrap();
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+
+    let run = Command::new(&binary).output().unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+    assert!(run.status.success());
+
+    String::from_utf8(run.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|bits| f32::from_bits(bits.parse().unwrap()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Compiles the exported Rust with a `main` that prints the bits of the outputs for every row of inputs
+fn run_exported_rust(genome: &Genome, name: &str, inputs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let rows: Vec<String> = inputs
+        .iter()
+        .map(|row| {
+            let values: Vec<String> = row.iter().map(|value| format!("{:?}_f32", value)).collect();
+            format!("[{}]", values.join(", "))
+        })
+        .collect();
+    let mut code = export::genome_to_rust(genome, "controller");
+    code.push_str(&format!(
+        "
+fn main() {{
+    for inputs in [{}].iter() {{
+        let outputs: Vec<String> = controller(inputs).iter().map(|o| o.to_bits().to_string()).collect();
+        println!(\"{{}}\", outputs.join(\" \"));
+    }}
+}}
+",
+        rows.join(", ")
+    ));
+
+    compile_and_run(
+        "rustc",
+        &["--edition=2018"],
+        "main.rs",
+        &code,
+        &format!("{}-rust", name),
+    )
+}
+
+/// The same for the exported C, compiled without contracting floating point math.
+/// None when there is no C compiler
+fn run_exported_c(genome: &Genome, name: &str, inputs: &[Vec<f32>]) -> Option<Vec<Vec<f32>>> {
+    if Command::new("cc").arg("--version").output().is_err() {
+        return None;
+    }
+
+    let network = CompiledNetwork::from_genome(genome);
+    let rows: Vec<String> = inputs
+        .iter()
+        .map(|row| {
+            let values: Vec<String> = row.iter().map(|value| format!("{:?}f", value)).collect();
+            format!("{{{}}}", values.join(", "))
+        })
+        .collect();
+    let mut code = export::genome_to_c(genome, "controller");
+    code.push_str(&format!(
+        "
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {{
+    const float inputs[][{}] = {{{}}};
+    float outputs[{}];
+    for (int row = 0; row < {}; row++) {{
+        controller(inputs[row], outputs);
+        for (int i = 0; i < {}; i++) {{
+            unsigned int bits;
+            memcpy(&bits, &outputs[i], sizeof bits);
+            printf(i == 0 ? \"%u\" : \" %u\", bits);
+        }}
+        printf(\"\\n\");
+    }}
+    return 0;
+}}
+",
+        network.get_input_count().max(1),
+        rows.join(", "),
+        network.get_output_count().max(1),
+        inputs.len(),
+        network.get_output_count()
+    ));
+
+    Some(compile_and_run(
+        "cc",
+        &["-std=c99", "-ffp-contract=off", "-lm"],
+        "main.c",
+        &code,
+        &format!("{}-c", name),
+    ))
+}
+
+fn assert_same_outputs(genome: &Genome, name: &str, inputs: &[Vec<f32>]) {
+    let mut network = CompiledNetwork::from_genome(genome);
+    let expected: Vec<Vec<f32>> = inputs.iter().map(|row| network.activate(row)).collect();
+
+    // Bits of every output, NaNs only have to agree on being NaN
+    let bits = |values: &[f32]| -> Vec<Option<u32>> {
+        values
+            .iter()
+            .map(|value| {
+                if value.is_nan() {
+                    None
+                } else {
+                    Some(value.to_bits())
+                }
+            })
+            .collect()
+    };
+    let assert_same = |language: &str, exported: &[Vec<f32>]| {
+        assert_eq!(exported.len(), inputs.len());
+        for ((row, outputs), expected) in inputs.iter().zip(exported).zip(&expected) {
+            assert_eq!(
+                bits(outputs),
+                bits(expected),
+                "{} export, inputs {:?}",
+                language,
+                row
+            );
+        }
+    };
+
+    assert_same("Rust", &run_exported_rust(genome, name, inputs));
+    match run_exported_c(genome, name, inputs) {
+        Some(exported) => assert_same("C", &exported),
+        None => eprintln!("no C compiler, only the Rust export of {} is checked", name),
+    }
+}
+
+#[test]
+fn exports_match_the_phenotype() {
+    let mut genome = Genome::with_hidden_layer(3, 2, 4, &mut Counter::new(), &mut Counter::new());
+    genome.get_connection_genes_mut()[5].disable();
+    let inputs = vec![
+        vec![0.0, 0.0, 1.0],
+        vec![1.0, 0.0, 1.0],
+        vec![0.5, -0.25, 1.0],
+        vec![-3.0, 7.5, 1.0],
+        vec![0.1, 0.2, 0.3],
+    ];
+
+    assert_same_outputs(&genome, "hidden-layer", &inputs);
+    let two_inputs: Vec<Vec<f32>> = inputs.iter().map(|row| row[..2].to_vec()).collect();
+    assert_same_outputs(&small_genome(), "small", &two_inputs);
+
+    // Non-finite weights have to compile and agree too
+    let mut non_finite = small_genome();
+    non_finite
+        .get_connection_gene_mut(3)
+        .unwrap()
+        .set_weight(f32::INFINITY);
+    non_finite
+        .get_connection_gene_mut(4)
+        .unwrap()
+        .set_weight(f32::NEG_INFINITY);
+    assert_same_outputs(&non_finite, "infinite", &[vec![1.0, 0.5], vec![-1.0, 2.0]]);
+
+    non_finite
+        .get_connection_gene_mut(2)
+        .unwrap()
+        .set_weight(f32::NAN);
+    assert_same_outputs(&non_finite, "nan", &[vec![1.0, 0.5]]);
+}
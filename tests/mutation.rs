@@ -0,0 +1,440 @@
+use neat_java::Config;
+use neat_java::ConnectionGene;
+use neat_java::Counter;
+use neat_java::CrossoverMethod;
+use neat_java::Genome;
+use neat_java::NodeGene;
+use neat_java::NodeGeneType;
+use neat_java::WeightMutation;
+
+/// A starting genome grown by a number of random add node and add connection mutations
+fn grown_genome(
+    mutations: usize,
+    node_innovation: &mut Counter,
+    connection_innovation: &mut Counter,
+) -> Genome {
+    let mut genome = Genome::fully_connected(3, 2, node_innovation, connection_innovation);
+    let config = Config::new(0, 0);
+
+    for _ in 0..mutations {
+        genome.add_node_mutation(connection_innovation, node_innovation);
+        genome.add_connection_mutation(connection_innovation, 100, &config);
+    }
+
+    genome
+}
+
+/// Every connection points at existing nodes, and every input and output still has a connection
+fn assert_consistent(genome: &Genome) {
+    for con in genome.get_connection_genes() {
+        assert!(genome.get_node_gene(con.get_in_node()).is_some());
+        assert!(genome.get_node_gene(con.get_out_node()).is_some());
+    }
+
+    for node in genome.get_node_genes() {
+        if node.get_type() != NodeGeneType::HIDDEN {
+            assert!(genome
+                .get_connection_genes()
+                .iter()
+                .any(|con| con.get_in_node() == node.get_id() || con.get_out_node() == node.get_id()));
+        }
+    }
+}
+
+/// `assert_consistent`, and the connection of every input and output is expressed, so none is cut off in the phenotype.
+///
+/// Crossover may inherit a disabled copy of the last expressed connection, so this only holds for the delete mutations
+fn assert_connected(genome: &Genome) {
+    assert_consistent(genome);
+
+    for node in genome.get_node_genes() {
+        if node.get_type() != NodeGeneType::HIDDEN {
+            let id = node.get_id();
+            assert!(genome
+                .get_connection_genes()
+                .iter()
+                .any(|con| con.is_expressed()
+                    && (con.get_in_node() == id || con.get_out_node() == id)));
+        }
+    }
+}
+
+fn count_nodes(genome: &Genome, node_type: NodeGeneType) -> usize {
+    genome
+        .get_node_genes()
+        .iter()
+        .filter(|node| node.get_type() == node_type)
+        .count()
+}
+
+#[test]
+fn delete_mutations_keep_inputs_and_outputs() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+
+    for _ in 0..20 {
+        let mut genome = grown_genome(15, &mut node_innovation, &mut connection_innovation);
+
+        for _ in 0..100 {
+            genome.delete_connection_mutation();
+            genome.delete_node_mutation();
+            assert_connected(&genome);
+        }
+
+        assert_eq!(count_nodes(&genome, NodeGeneType::INPUT), 3);
+        assert_eq!(count_nodes(&genome, NodeGeneType::OUTPUT), 2);
+    }
+}
+
+#[test]
+fn delete_node_removes_attached_connections() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = grown_genome(1, &mut node_innovation, &mut connection_innovation);
+
+    // The nodes of the split connection keep their other connections, so the new hidden node can go
+    let connections = genome.get_connection_genes().len();
+    assert!(genome.delete_node_mutation());
+
+    assert_eq!(count_nodes(&genome, NodeGeneType::HIDDEN), 0);
+    assert!(genome.get_connection_genes().len() < connections);
+    assert_connected(&genome);
+}
+
+#[test]
+fn disabled_connections_dont_keep_inputs_and_outputs_connected() {
+    // Splitting the only connection leaves it disabled, the input and output now depend on the hidden node
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome =
+        Genome::fully_connected(1, 1, &mut node_innovation, &mut connection_innovation);
+    genome.add_node_mutation(&mut connection_innovation, &mut node_innovation);
+
+    assert!(!genome.delete_node_mutation());
+
+    // Only the disabled connection can go
+    assert!(genome.delete_connection_mutation());
+    assert!(!genome.delete_connection_mutation());
+    assert_eq!(genome.get_connection_genes().len(), 2);
+    assert_connected(&genome);
+}
+
+#[test]
+fn delete_mutations_without_candidates_change_nothing() {
+    // The only connection is the last one of both its nodes, and there is no hidden node
+    let mut genome = Genome::fully_connected(1, 1, &mut Counter::new(), &mut Counter::new());
+    let original = genome.clone();
+
+    assert!(!genome.delete_connection_mutation());
+    assert!(!genome.delete_node_mutation());
+    assert_eq!(genome, original);
+}
+
+#[test]
+fn crossover_of_simplified_genomes_is_consistent() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+
+    for _ in 0..20 {
+        let mut parent1 = grown_genome(10, &mut node_innovation, &mut connection_innovation);
+        let mut parent2 = grown_genome(10, &mut node_innovation, &mut connection_innovation);
+        for _ in 0..5 {
+            parent1.delete_node_mutation();
+            parent2.delete_connection_mutation();
+        }
+
+        let child = Genome::crossover(&parent1, &parent2, 0.3);
+        assert_consistent(&child);
+    }
+}
+
+/// Whether the expressed connections form a directed acyclic graph
+fn is_acyclic(genome: &Genome) -> bool {
+    let mut remaining: Vec<(i32, i32)> = genome
+        .get_connection_genes()
+        .iter()
+        .filter(|con| con.is_expressed())
+        .map(|con| (con.get_in_node(), con.get_out_node()))
+        .collect();
+
+    // Repeatedly drop the connections leaving nodes that have no incoming connections left
+    loop {
+        let sources: Vec<i32> = remaining
+            .iter()
+            .map(|(in_node, _)| *in_node)
+            .filter(|in_node| !remaining.iter().any(|(_, out_node)| out_node == in_node))
+            .collect();
+        if sources.is_empty() {
+            return remaining.is_empty();
+        }
+        remaining.retain(|(in_node, _)| !sources.contains(in_node));
+    }
+}
+
+#[test]
+fn toggle_enable_never_creates_cycles() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+
+    for _ in 0..20 {
+        let mut genome = grown_genome(15, &mut node_innovation, &mut connection_innovation);
+        let connections = genome.get_connection_genes().len();
+
+        for _ in 0..200 {
+            genome.toggle_enable_mutation();
+            assert!(is_acyclic(&genome));
+        }
+
+        // Toggling never adds or removes genes
+        assert_eq!(genome.get_connection_genes().len(), connections);
+    }
+}
+
+#[test]
+fn crossover_never_creates_cycles() {
+    // Hidden nodes 2 and 3 are connected both ways, each parent only expresses one direction
+    let mut parent1 = Genome::new();
+    parent1.add_node_gene(NodeGene::new(NodeGeneType::INPUT, 1));
+    parent1.add_node_gene(NodeGene::new(NodeGeneType::HIDDEN, 2));
+    parent1.add_node_gene(NodeGene::new(NodeGeneType::HIDDEN, 3));
+    parent1.add_node_gene(NodeGene::new(NodeGeneType::OUTPUT, 4));
+    parent1.add_connection_gene(ConnectionGene::new(1, 2, 1.0, true, 1));
+    parent1.add_connection_gene(ConnectionGene::new(2, 3, 1.0, true, 2));
+    parent1.add_connection_gene(ConnectionGene::new(3, 2, 1.0, false, 3));
+    parent1.add_connection_gene(ConnectionGene::new(3, 4, 1.0, true, 4));
+    let mut parent2 = parent1.clone();
+    parent2.get_connection_genes_mut()[1].disable();
+    parent2.get_connection_genes_mut()[2].enable();
+
+    let mut config = Config::new(0, 0);
+    config.disabled_gene_inheriting_chance = 0.0;
+    for crossover in &[
+        CrossoverMethod::Uniform,
+        CrossoverMethod::Averaging,
+        CrossoverMethod::SinglePoint,
+    ] {
+        config.crossover = *crossover;
+        for _ in 0..50 {
+            assert!(is_acyclic(&Genome::crossover_with(
+                &parent1, &parent2, false, &config
+            )));
+        }
+    }
+
+    // Nor do grown parents with toggled genes, inheriting from both
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    config.crossover_equal_fitness_inherits_both = true;
+    for _ in 0..20 {
+        let mut parent1 = grown_genome(15, &mut node_innovation, &mut connection_innovation);
+        let mut parent2 = grown_genome(15, &mut node_innovation, &mut connection_innovation);
+        for _ in 0..20 {
+            parent1.toggle_enable_mutation();
+            parent2.toggle_enable_mutation();
+        }
+
+        let child = Genome::crossover_with(&parent1, &parent2, true, &config);
+        assert!(is_acyclic(&child));
+        assert_consistent(&child);
+    }
+}
+
+#[test]
+fn toggle_enable_re_enables_connections() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = Genome::fully_connected(2, 1, &mut node_innovation, &mut connection_innovation);
+    for con in genome.get_connection_genes_mut() {
+        con.disable();
+    }
+
+    // Without hidden nodes no connection can close a cycle, so the first toggle enables one
+    assert!(genome.toggle_enable_mutation());
+    assert_eq!(
+        genome
+            .get_connection_genes()
+            .iter()
+            .filter(|con| con.is_expressed())
+            .count(),
+        1
+    );
+    // Nothing to toggle without connections
+    assert!(!Genome::new().toggle_enable_mutation());
+}
+
+fn weights(genome: &Genome) -> Vec<f32> {
+    genome
+        .get_connection_genes()
+        .iter()
+        .map(|con| con.get_weight())
+        .collect()
+}
+
+#[test]
+fn weight_mutation_respects_bounds() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = Genome::fully_connected(5, 3, &mut node_innovation, &mut connection_innovation);
+
+    let mut config = Config::new(0, 0);
+    config.weight_perturbation_sigma = 10.0;
+    config.weight_min = -1.5;
+    config.weight_max = 0.5;
+
+    for weight_mutation in &[
+        WeightMutation::Gaussian,
+        WeightMutation::Multiplicative,
+        WeightMutation::OneGene,
+    ] {
+        config.weight_mutation = *weight_mutation;
+        for _ in 0..50 {
+            genome.mutation(&config);
+            assert!(weights(&genome).iter().all(|w| *w >= -1.5 && *w <= 0.5));
+        }
+    }
+}
+
+#[test]
+fn new_connections_respect_bounds() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = grown_genome(5, &mut node_innovation, &mut connection_innovation);
+    for con in genome.get_connection_genes_mut() {
+        con.set_weight(0.0);
+    }
+
+    let mut config = Config::new(0, 0);
+    config.weight_min = -0.25;
+    config.weight_max = 0.1;
+
+    let before = genome.get_connection_genes().len();
+    for _ in 0..20 {
+        genome.add_connection_mutation(&mut connection_innovation, 100, &config);
+    }
+    assert!(genome.get_connection_genes().len() > before);
+    assert!(weights(&genome).iter().all(|w| *w >= -0.25 && *w <= 0.1));
+}
+
+#[test]
+fn default_weight_mutation_is_unbounded_multiplicative() {
+    let config = Config::new(0, 0);
+    assert_eq!(config.weight_mutation, WeightMutation::Multiplicative);
+    assert_eq!(config.weight_min, f32::NEG_INFINITY);
+    assert_eq!(config.weight_max, f32::INFINITY);
+
+    let read = Config::from_text(&config.to_text()).unwrap();
+    assert_eq!(read.weight_min, f32::NEG_INFINITY);
+    assert_eq!(read.weight_max, f32::INFINITY);
+
+    // Scaled weights grow as large as they like
+    let mut genome = Genome::fully_connected(5, 4, &mut Counter::new(), &mut Counter::new());
+    for con in genome.get_connection_genes_mut() {
+        con.set_weight(1000.0);
+    }
+    let mut config = config;
+    config.pertrubing_rate = 1.0;
+    genome.mutation(&config);
+    assert!(weights(&genome).iter().any(|w| w.abs() > 100.0));
+}
+
+#[test]
+fn weight_mutation_gene_rate() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = Genome::fully_connected(5, 3, &mut node_innovation, &mut connection_innovation);
+    let before = weights(&genome);
+
+    // No gene is picked
+    let mut config = Config::new(0, 0);
+    config.weight_mutation_gene_rate = 0.0;
+    genome.mutation(&config);
+    assert_eq!(weights(&genome), before);
+
+    // One gene changes regardless of the gene rate
+    config.weight_mutation = WeightMutation::OneGene;
+    genome.mutation(&config);
+    let changed = weights(&genome)
+        .iter()
+        .zip(before.iter())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert_eq!(changed, 1);
+}
+
+#[test]
+fn self_adaptive_step_size() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = Genome::fully_connected(5, 3, &mut node_innovation, &mut connection_innovation);
+
+    let mut config = Config::new(0, 0);
+    config.self_adaptive_mutation = true;
+    config.mutation_step_min = 0.1;
+    config.mutation_step_max = 1.0;
+
+    let mut steps: Vec<f32> = Vec::new();
+    for _ in 0..50 {
+        genome.mutation(&config);
+        steps.push(genome.get_mutation_step());
+    }
+
+    assert!(steps.iter().all(|step| *step >= 0.1 && *step <= 1.0));
+    assert!(steps.iter().any(|step| *step != steps[0]));
+
+    // Without self-adaptation the step size is left alone
+    config.self_adaptive_mutation = false;
+    let step = genome.get_mutation_step();
+    genome.mutation(&config);
+    assert_eq!(genome.get_mutation_step(), step);
+}
+
+#[test]
+fn step_size_sets_how_far_weights_move() {
+    let mut genome = Genome::fully_connected(5, 3, &mut Counter::new(), &mut Counter::new());
+    for con in genome.get_connection_genes_mut() {
+        con.set_weight(1.0);
+    }
+
+    // The step size is pinned by its bounds, so only the genome's own sigma differs between the runs
+    let mean_change = |weight_mutation: WeightMutation, step: f32| {
+        let mut config = Config::new(0, 0);
+        config.weight_mutation = weight_mutation;
+        config.pertrubing_rate = 1.0;
+        config.self_adaptive_mutation = true;
+        config.mutation_step_min = step;
+        config.mutation_step_max = step;
+
+        let mut total = 0.0;
+        for _ in 0..100 {
+            let mut mutated = genome.clone();
+            mutated.mutation(&config);
+            total += mutated
+                .get_connection_genes()
+                .iter()
+                .map(|con| (con.get_weight() - 1.0).abs())
+                .sum::<f32>();
+        }
+
+        total / (100 * genome.get_connection_genes().len()) as f32
+    };
+
+    for weight_mutation in &[WeightMutation::Gaussian, WeightMutation::Multiplicative] {
+        let small = mean_change(*weight_mutation, 0.01);
+        let large = mean_change(*weight_mutation, 1.0);
+        assert!(small < 0.05, "{:?} moved {} with a step of 0.01", weight_mutation, small);
+        assert!(large > 0.5, "{:?} moved {} with a step of 1.0", weight_mutation, large);
+    }
+}
+
+#[test]
+fn crossover_averages_step_sizes() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut parent1 = Genome::fully_connected(2, 1, &mut node_innovation, &mut connection_innovation);
+    let mut parent2 = parent1.clone();
+    parent1.set_mutation_step(0.2);
+    parent2.set_mutation_step(0.6);
+
+    let child = Genome::crossover(&parent1, &parent2, 0.3);
+    assert!((child.get_mutation_step() - 0.4).abs() < 1e-6);
+}
@@ -0,0 +1,67 @@
+use neat_java::CompiledNetwork;
+use neat_java::Config;
+use neat_java::Counter;
+use neat_java::Genome;
+use neat_java::Network;
+
+use rand::Rng;
+
+/// A starting genome grown by a number of random structural mutations
+fn grown_genome(mutations: usize) -> Genome {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = Genome::fully_connected(3, 2, &mut node_innovation, &mut connection_innovation);
+    let config = Config::new(0, 0);
+
+    for _ in 0..mutations {
+        genome.add_node_mutation(&mut connection_innovation, &mut node_innovation);
+        genome.add_connection_mutation(&mut connection_innovation, 100, &config);
+        genome.mutation(&config);
+    }
+
+    genome
+}
+
+#[test]
+fn compiled_network_matches_network() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..50 {
+        let genome = grown_genome(10);
+        let mut network = Network::from_genome(&genome);
+        let mut compiled = CompiledNetwork::from_genome(&genome);
+
+        assert_eq!(compiled.get_input_count(), network.get_input_count());
+        assert_eq!(compiled.get_output_count(), network.get_output_count());
+
+        for _ in 0..10 {
+            let inputs: Vec<f32> = (0..3).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+            assert_eq!(compiled.activate(&inputs), network.activate(&inputs));
+        }
+    }
+}
+
+#[test]
+fn compiled_network_of_empty_genome() {
+    let mut compiled = CompiledNetwork::from_genome(&Genome::new());
+
+    assert_eq!(compiled.get_node_count(), 0);
+    assert!(compiled.activate(&[1.0, 2.0]).is_empty());
+}
+
+#[test]
+fn predict_batch_matches_activate() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..20 {
+        let genome = grown_genome(10);
+        let mut compiled = CompiledNetwork::from_genome(&genome);
+
+        let rows = 64;
+        let inputs: Vec<f32> = (0..rows * 3).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+        let batch = compiled.predict_batch(&inputs, rows);
+
+        let expected: Vec<f32> = inputs.chunks(3).flat_map(|row| compiled.activate(row)).collect();
+        assert_eq!(batch, expected);
+    }
+}
@@ -0,0 +1,268 @@
+mod common;
+
+use common::RandomWeights;
+use common::WeightSum;
+use neat_java::evaluator::novelty::behaviour_distance;
+use neat_java::tasks::pole_balancing::CartPole;
+use neat_java::ArchiveInsertion;
+use neat_java::Checkpoint;
+use neat_java::Config;
+use neat_java::Counter;
+use neat_java::Dataset;
+use neat_java::DatasetTask;
+use neat_java::EpisodeRunner;
+use neat_java::Evaluator;
+use neat_java::FitnessGenomeProvider;
+use neat_java::Genome;
+use neat_java::Metric;
+use neat_java::NoveltyArchive;
+use neat_java::PoleBalancing;
+use neat_java::PoleBalancingTask;
+use neat_java::XorTask;
+
+#[test]
+fn novelty_is_the_mean_distance_to_the_nearest_neighbours() {
+    let archive = NoveltyArchive::new();
+    let behaviours = vec![vec![0.0, 0.0], vec![3.0, 4.0], vec![6.0, 8.0]];
+
+    assert_eq!(behaviour_distance(&behaviours[0], &behaviours[1]), 5.0);
+    assert_eq!(archive.novelty_scores(&behaviours, 1), vec![5.0, 5.0, 5.0]);
+    assert_eq!(archive.novelty_scores(&behaviours, 2), vec![7.5, 5.0, 7.5]);
+
+    // Fewer neighbours than k use all of them, a lone behaviour has no novelty
+    assert_eq!(archive.novelty_scores(&behaviours, 10), vec![7.5, 5.0, 7.5]);
+    assert_eq!(archive.novelty_scores(&behaviours[..1], 3), vec![0.0]);
+}
+
+#[test]
+fn archived_behaviours_are_neighbours() {
+    let mut config = Config::new(0, 0);
+    config.novelty_archive_insertion = ArchiveInsertion::MostNovel;
+    config.novelty_archive_per_generation = 1;
+
+    let mut archive = NoveltyArchive::new();
+    archive.insert(&[vec![0.0], vec![10.0]], &[1.0, 2.0], &config);
+    assert_eq!(archive.get_behaviours(), &[vec![10.0]]);
+
+    // The archived behaviour is closer to the second behaviour than the first one is
+    assert_eq!(archive.novelty_scores(&[vec![0.0], vec![9.0]], 1), vec![9.0, 1.0]);
+}
+
+#[test]
+fn archive_insertion_rules() {
+    let behaviours: Vec<Vec<f32>> = (0..10).map(|i| vec![i as f32]).collect();
+    let novelties: Vec<f32> = (0..10).map(|i| i as f32).collect();
+    let mut config = Config::new(0, 0);
+
+    config.novelty_archive_insertion = ArchiveInsertion::Threshold;
+    config.novelty_archive_threshold = 6.5;
+    let mut archive = NoveltyArchive::new();
+    archive.insert(&behaviours, &novelties, &config);
+    assert_eq!(archive.get_behaviours(), &[vec![7.0], vec![8.0], vec![9.0]]);
+
+    config.novelty_archive_insertion = ArchiveInsertion::Random;
+    config.novelty_archive_probability = 1.0;
+    let mut archive = NoveltyArchive::new();
+    archive.insert(&behaviours, &novelties, &config);
+    assert_eq!(archive.get_behaviours().len(), 10);
+
+    config.novelty_archive_probability = 0.0;
+    let mut archive = NoveltyArchive::new();
+    archive.insert(&behaviours, &novelties, &config);
+    assert!(archive.get_behaviours().is_empty());
+
+    config.novelty_archive_insertion = ArchiveInsertion::MostNovel;
+    config.novelty_archive_per_generation = 2;
+    let mut archive = NoveltyArchive::new();
+    archive.insert(&behaviours, &novelties, &config);
+    assert_eq!(archive.get_behaviours(), &[vec![9.0], vec![8.0]]);
+}
+
+#[test]
+fn archive_drops_the_oldest_behaviours() {
+    let mut config = Config::new(0, 0);
+    config.novelty_archive_insertion = ArchiveInsertion::Random;
+    config.novelty_archive_probability = 1.0;
+    config.novelty_archive_max_size = 3;
+
+    let mut archive = NoveltyArchive::new();
+    archive.insert(&[vec![1.0], vec![2.0]], &[0.0, 0.0], &config);
+    archive.insert(&[vec![3.0], vec![4.0]], &[0.0, 0.0], &config);
+    assert_eq!(archive.get_behaviours(), &[vec![2.0], vec![3.0], vec![4.0]]);
+}
+
+#[test]
+fn novelty_search_keeps_the_task_fitness() {
+    let mut config = Config::new(30, 5);
+    config.novelty_search = true;
+    config.novelty_k = 5;
+    config.novelty_archive_insertion = ArchiveInsertion::MostNovel;
+
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = XorTask::starting_genome(&mut node_innovation, &mut connection_innovation);
+
+    let mut evaluator = Evaluator::new();
+    evaluator.init(&config, &genome, Box::new(RandomWeights {}));
+    for _ in 0..5 {
+        evaluator
+            .evaluate_generation(
+                Box::new(XorTask::new()),
+                &mut node_innovation,
+                &mut connection_innovation,
+            )
+            .unwrap();
+
+        // The champion and the statistics are by the XOR fitness, not the novelty score
+        let results = evaluator.get_last_generation_results();
+        let best = results
+            .iter()
+            .map(|result| result.get_objective_fitness())
+            .fold(f32::MIN, f32::max);
+        let statistics = evaluator.get_history().last().unwrap();
+        assert_eq!(statistics.best_fitness, best);
+        assert_eq!(evaluator.get_fittest_genome().get_objective_fitness(), best);
+        assert!(statistics.mean_novelty > 0.0);
+    }
+
+    assert_eq!(evaluator.get_novelty_archive().get_behaviours().len(), 5);
+    assert_eq!(evaluator.get_history()[4].novelty_archive_size, 5);
+}
+
+#[test]
+fn inconsistent_behaviours_are_rejected() {
+    let archive = NoveltyArchive::from_behaviours(vec![vec![1.0, 2.0]]);
+
+    assert!(archive
+        .check_behaviours(&[vec![3.0, 4.0], vec![5.0, 6.0]])
+        .is_ok());
+    assert!(archive
+        .check_behaviours(&[vec![3.0, 4.0], Vec::new()])
+        .is_err());
+    assert!(archive.check_behaviours(&[vec![3.0]]).is_err());
+    assert!(NoveltyArchive::new()
+        .check_behaviours(&[vec![1.0], vec![2.0, 3.0]])
+        .is_err());
+    assert!(NoveltyArchive::new().check_behaviours(&[]).is_ok());
+}
+
+#[test]
+fn tasks_without_behaviours_fail_to_evaluate() {
+    let mut config = Config::new(10, 1);
+    config.novelty_search = true;
+
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = XorTask::starting_genome(&mut node_innovation, &mut connection_innovation);
+
+    let mut evaluator = Evaluator::new();
+    evaluator.init(&config, &genome, Box::new(RandomWeights {}));
+    let genomes = evaluator.get_genomes().to_vec();
+
+    assert!(!WeightSum {}.has_behaviour());
+    let result = evaluator.evaluate_generation(
+        Box::new(WeightSum {}),
+        &mut node_innovation,
+        &mut connection_innovation,
+    );
+    assert!(result.unwrap_err().contains("empty behaviour descriptor"));
+    assert_eq!(evaluator.get_genomes(), &genomes[..]);
+    assert_eq!(evaluator.get_generation(), 0);
+}
+
+#[test]
+fn built_in_tasks_describe_behaviours() {
+    let genome = |inputs: usize, scale: f32| {
+        let mut genome =
+            Genome::fully_connected(inputs, 1, &mut Counter::new(), &mut Counter::new());
+        for (index, con) in genome.get_connection_genes_mut().iter_mut().enumerate() {
+            con.set_weight(index as f32 * scale - 1.0);
+        }
+        genome
+    };
+    let dataset = Dataset::new(
+        vec![vec![0.0], vec![0.5], vec![1.0], vec![1.5], vec![2.0]],
+        vec![vec![0.0], vec![0.0], vec![1.0], vec![1.0], vec![1.0]],
+    );
+    let tasks: Vec<(Box<dyn FitnessGenomeProvider>, usize, usize)> = vec![
+        (Box::new(XorTask::new()), XorTask::INPUT_COUNT, 4),
+        (
+            Box::new(PoleBalancingTask::new(PoleBalancing::SinglePole, 50)),
+            5,
+            4,
+        ),
+        (
+            Box::new(PoleBalancingTask::new(PoleBalancing::DoublePole, 50)),
+            7,
+            6,
+        ),
+        (
+            Box::new(PoleBalancingTask::new(
+                PoleBalancing::DoublePoleNoVelocities,
+                50,
+            )),
+            4,
+            6,
+        ),
+        (
+            Box::new(DatasetTask::new(&dataset, 0.4, Metric::Accuracy)),
+            2,
+            3,
+        ),
+        (Box::new(EpisodeRunner::new(CartPole::new(), 3, 50)), 5, 5),
+    ];
+
+    // The descriptor has the same length for every genome of a task
+    for (task, inputs, length) in tasks {
+        assert!(task.has_behaviour());
+        for scale in &[0.5, -2.0] {
+            let (_, behaviour) = task.fitness_and_behaviour(&genome(inputs, *scale));
+            assert_eq!(behaviour.len(), length);
+        }
+    }
+}
+
+#[test]
+fn resuming_keeps_the_archive() {
+    let mut config = Config::new(30, 5);
+    config.novelty_search = true;
+    config.novelty_archive_insertion = ArchiveInsertion::MostNovel;
+
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = XorTask::starting_genome(&mut node_innovation, &mut connection_innovation);
+
+    let mut evaluator = Evaluator::new();
+    evaluator.init(&config, &genome, Box::new(RandomWeights {}));
+    evaluator
+        .evaluate_generation(
+            Box::new(XorTask::new()),
+            &mut node_innovation,
+            &mut connection_innovation,
+        )
+        .unwrap();
+    let archive = evaluator.get_novelty_archive().clone();
+
+    let mut resumed = Evaluator::new();
+    resumed.resume(&Checkpoint::new(
+        &evaluator,
+        &node_innovation,
+        &connection_innovation,
+        "xor",
+    ));
+    assert_eq!(resumed.get_novelty_archive(), &archive);
+
+    // The next generation adds to the resumed archive instead of starting over
+    resumed
+        .evaluate_generation(
+            Box::new(XorTask::new()),
+            &mut node_innovation,
+            &mut connection_innovation,
+        )
+        .unwrap();
+    assert_eq!(
+        resumed.get_novelty_archive().get_behaviours()[..archive.get_behaviours().len()],
+        archive.get_behaviours()[..]
+    );
+    assert_eq!(resumed.get_novelty_archive().get_behaviours().len(), 2);
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use common::evolve;
+use neat_java::Config;
+use neat_java::Counter;
+use neat_java::Evaluator;
+use neat_java::GenerationStatistics;
+use neat_java::GenesisGenomeProvider;
+use neat_java::Genome;
+use neat_java::SearchPhase;
+use neat_java::XorTask;
+
+struct Clones {}
+
+impl GenesisGenomeProvider for Clones {
+    fn generate_genesis_genome(&self, genome: &Genome) -> Genome {
+        genome.clone()
+    }
+}
+
+fn run(
+    config: &Config,
+    starting_genome: fn(&mut Counter, &mut Counter) -> Genome,
+) -> Vec<GenerationStatistics> {
+    evolve(
+        config,
+        starting_genome,
+        Box::new(Clones {}),
+        || Box::new(XorTask::new()),
+        config.get_generation_count(),
+    )
+    .get_history()
+    .to_vec()
+}
+
+/// The XOR inputs and output with two hidden nodes, 8 connections of which the hidden ones can be deleted
+fn hidden_layer(node_innovation: &mut Counter, connection_innovation: &mut Counter) -> Genome {
+    Genome::with_hidden_layer(
+        XorTask::INPUT_COUNT,
+        XorTask::OUTPUT_COUNT,
+        2,
+        node_innovation,
+        connection_innovation,
+    )
+}
+
+fn phased_config() -> Config {
+    let mut config = Config::new(20, 10);
+    config.phased_search = true;
+    config.phased_search_complexity_threshold = 2.0;
+    config.phased_search_stall_generations = 3;
+
+    config
+}
+
+#[test]
+fn phases_alternate() {
+    let config = phased_config();
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = XorTask::starting_genome(&mut node_innovation, &mut connection_innovation);
+    let mut evaluator = Evaluator::new();
+    evaluator.init(&config, &genome, Box::new(Clones {}));
+
+    // (complexity, phase after the update, ceiling after the update)
+    let steps = [
+        // The first ceiling is 2 above the starting complexity
+        (10.0, SearchPhase::Complexifying, 12.0),
+        (11.0, SearchPhase::Complexifying, 12.0),
+        (12.0, SearchPhase::Complexifying, 12.0),
+        // Passing the ceiling starts simplifying
+        (12.5, SearchPhase::Simplifying, 12.0),
+        (11.0, SearchPhase::Simplifying, 12.0),
+        (11.0, SearchPhase::Simplifying, 12.0),
+        (11.5, SearchPhase::Simplifying, 12.0),
+        // 3 generations without a new lowest complexity grow again, from where the pruning ended
+        (11.0, SearchPhase::Complexifying, 13.0),
+        (13.0, SearchPhase::Complexifying, 13.0),
+        (13.5, SearchPhase::Simplifying, 13.0),
+        // A new lowest complexity restarts the stall count
+        (12.0, SearchPhase::Simplifying, 13.0),
+        (12.0, SearchPhase::Simplifying, 13.0),
+        (11.0, SearchPhase::Simplifying, 13.0),
+        (11.0, SearchPhase::Simplifying, 13.0),
+        (11.0, SearchPhase::Simplifying, 13.0),
+        (11.0, SearchPhase::Complexifying, 13.0),
+    ];
+    for (complexity, phase, ceiling) in steps.iter() {
+        evaluator.update_phase(*complexity);
+        assert_eq!(evaluator.get_phase(), *phase, "complexity {}", complexity);
+        assert_eq!(
+            evaluator.get_complexity_ceiling(),
+            *ceiling,
+            "complexity {}",
+            complexity
+        );
+    }
+}
+
+#[test]
+fn deletions_wait_for_the_simplifying_phase() {
+    let mut config = phased_config();
+    config.add_connection_rate = 0.0;
+    config.add_node_rate = 0.0;
+    config.delete_connection_rate = 1.0;
+    config.delete_node_rate = 1.0;
+    config.phased_search_complexity_threshold = 1000.0;
+
+    // The population never grows past the ceiling, so nothing is ever deleted
+    let history = run(&config, hidden_layer);
+    assert!(history
+        .iter()
+        .all(|statistics| statistics.phase == SearchPhase::Complexifying));
+    assert!(history
+        .iter()
+        .all(|statistics| statistics.mean_connections == 8.0));
+
+    // Without phased search the delete rates apply every generation
+    config.phased_search = false;
+    let history = run(&config, hidden_layer);
+    assert!(history.last().unwrap().mean_connections < 8.0);
+}
+
+#[test]
+fn toggles_wait_for_the_complexifying_phase() {
+    let mut config = phased_config();
+    config.add_connection_rate = 0.0;
+    config.add_node_rate = 0.0;
+    config.toggle_enable_rate = 1.0;
+    // A negative threshold passes the ceiling right away, and the phase never stalls
+    config.phased_search_complexity_threshold = -1.0;
+    config.phased_search_stall_generations = 1000;
+
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = XorTask::starting_genome(&mut node_innovation, &mut connection_innovation);
+    let mut evaluator = Evaluator::new();
+    evaluator.init(&config, &genome, Box::new(Clones {}));
+    for _ in 0..config.get_generation_count() {
+        evaluator
+            .evaluate_generation(
+                Box::new(XorTask::new()),
+                &mut node_innovation,
+                &mut connection_innovation,
+            )
+            .unwrap();
+        assert_eq!(evaluator.get_phase(), SearchPhase::Simplifying);
+        assert!(evaluator.get_genomes().iter().all(|genome| genome
+            .get_connection_genes()
+            .iter()
+            .all(|con| con.is_expressed())));
+    }
+}
+
+#[test]
+fn phases_are_off_by_default() {
+    let history = run(&Config::new(20, 5), XorTask::starting_genome);
+
+    assert!(history
+        .iter()
+        .all(|statistics| statistics.phase == SearchPhase::Complexifying
+            && statistics.complexity_ceiling.is_none()));
+}
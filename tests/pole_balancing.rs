@@ -0,0 +1,149 @@
+use neat_java::tasks::pole_balancing::CartPole;
+use neat_java::tasks::pole_balancing::DoublePole;
+use neat_java::Environment;
+
+fn assert_state(cart_pole: &CartPole, expected: [f64; 4]) {
+    for (value, expected) in cart_pole.get_state().iter().zip(expected.iter()) {
+        assert!(
+            (value - expected).abs() < 1e-9,
+            "{:?} != {:?}",
+            cart_pole.get_state(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn cart_pole_steps_like_the_reference() {
+    // From rest a push to the right accelerates the cart right and the pole left
+    let mut cart_pole = CartPole::with_state(0.0, 0.0, 0.0, 0.0);
+    assert_eq!(cart_pole.act(&[1.0]), 1.0);
+    assert_state(
+        &cart_pole,
+        [0.0, 0.1951219512195122, 0.0, -0.2926829268292683],
+    );
+
+    assert_eq!(cart_pole.act(&[1.0]), 1.0);
+    assert_state(
+        &cart_pole,
+        [
+            0.0039024390243902443,
+            0.3902439024390244,
+            -0.005853658536585366,
+            -0.5853658536585366,
+        ],
+    );
+    assert_eq!(cart_pole.get_steps(), 2);
+
+    // An output of at most 0.5 pushes to the left
+    let mut cart_pole = CartPole::with_state(0.1, -0.2, 0.05, 0.3);
+    cart_pole.act(&[0.5]);
+    assert_state(
+        &cart_pole,
+        [0.096, -0.3957976546439626, 0.056, 0.6080233136061515],
+    );
+}
+
+#[test]
+fn episode_ends_past_the_failure_angle() {
+    // 12 degrees is 0.2094 radians
+    assert!(!CartPole::with_state(0.0, 0.0, 0.2, 0.0).is_done());
+    assert!(CartPole::with_state(0.0, 0.0, 0.21, 0.0).is_done());
+    assert!(CartPole::with_state(0.0, 0.0, -0.21, 0.0).is_done());
+
+    // Pushing away from a leaning pole lets it fall on the third step, which earns no reward
+    let mut cart_pole = CartPole::with_state(0.0, 0.0, 0.2, 0.0);
+    assert_eq!(cart_pole.act(&[0.0]), 1.0);
+    assert_eq!(cart_pole.act(&[0.0]), 1.0);
+    assert!(!cart_pole.is_done());
+    assert_eq!(cart_pole.act(&[0.0]), 0.0);
+    assert!(cart_pole.is_done());
+    assert!(cart_pole.get_state()[2] > 12f64.to_radians());
+}
+
+#[test]
+fn episode_ends_off_the_track() {
+    assert!(!CartPole::with_state(2.4, 0.0, 0.0, 0.0).is_done());
+    assert!(CartPole::with_state(2.5, 0.0, 0.0, 0.0).is_done());
+    assert!(CartPole::with_state(-2.5, 0.0, 0.0, 0.0).is_done());
+}
+
+#[test]
+fn random_starts_are_inside_the_bounds() {
+    for _ in 0..100 {
+        let cart_pole = CartPole::default();
+        assert!(!cart_pole.is_done());
+        assert_eq!(cart_pole.get_steps(), 0);
+    }
+}
+
+fn assert_double_pole_state(double_pole: &DoublePole, expected: [f64; 6]) {
+    for (value, expected) in double_pole.get_state().iter().zip(expected.iter()) {
+        assert!(
+            (value - expected).abs() < 1e-9,
+            "{:?} != {:?}",
+            double_pole.get_state(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn double_pole_steps_like_the_reference() {
+    // A full push to the right from rest, two Runge-Kutta steps of 0.01 s
+    let mut double_pole = DoublePole::with_state([0.0; 6], true);
+    assert_eq!(double_pole.act(&[1.0]), 1.0);
+    assert_double_pole_state(
+        &double_pole,
+        [
+            0.0019466029410460874,
+            0.19467340497555743,
+            -0.0029213333047088804,
+            -0.2922958998052316,
+            -0.029329741216025794,
+            -2.9467692055026897,
+        ],
+    );
+    assert_eq!(double_pole.get_steps(), 1);
+
+    // An output of 0.75 pushes with 5 N
+    let mut double_pole = DoublePole::with_state([0.1, -0.2, 0.05, 0.3, -0.02, 0.1], false);
+    double_pole.act(&[0.75]);
+    assert_double_pole_state(
+        &double_pole,
+        [
+            0.09696599142864391,
+            -0.10341026639529856,
+            0.05470512934139934,
+            0.17076323427048012,
+            -0.033123793913936474,
+            -1.4182779411625064,
+        ],
+    );
+}
+
+#[test]
+fn gruau_fitness_of_a_known_trajectory() {
+    // Without force or a tilt the poles stay upright, and the cart rolls on at 0.1 m/s, 0.002 m per step
+    let mut double_pole = DoublePole::with_state([0.0, 0.1, 0.0, 0.0, 0.0, 0.0], false);
+    for _ in 0..50 {
+        assert_eq!(double_pole.act(&[0.5]), 1.0);
+    }
+
+    // The jiggle term only counts after 100 steps
+    assert!((double_pole.gruau_fitness() - 0.1 * 50.0 / 1000.0).abs() < 1e-6);
+
+    for _ in 50..200 {
+        double_pole.act(&[0.5]);
+    }
+
+    // |x| + |x_dot| of the last 100 steps, the poles add nothing
+    let jiggle: f64 = (101..=200).map(|step| 0.002 * step as f64 + 0.1).sum();
+    let expected = 0.1 * 200.0 / 1000.0 + 0.9 * 0.75 / jiggle;
+    assert!(
+        (f64::from(double_pole.gruau_fitness()) - expected).abs() < 1e-6,
+        "{} != {}",
+        double_pole.gruau_fitness(),
+        expected
+    );
+}
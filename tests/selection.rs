@@ -0,0 +1,99 @@
+use neat_java::evaluator::selection::Rank;
+use neat_java::evaluator::selection::Roulette;
+use neat_java::evaluator::selection::StochasticUniversalSampling;
+use neat_java::evaluator::selection::Tournament;
+use neat_java::evaluator::selection::Truncation;
+use neat_java::Selection;
+
+/// Fitnesses sorted from fittest to least fit, like the evaluator passes them
+fn sorted_fitnesses(count: usize) -> Vec<f32> {
+    (0..count).rev().map(|i| i as f32).collect()
+}
+
+fn strategies() -> Vec<Box<dyn Selection>> {
+    vec![
+        Box::new(Truncation::new(0.2)),
+        Box::new(Tournament::new(3)),
+        Box::new(Roulette {}),
+        Box::new(StochasticUniversalSampling {}),
+        Box::new(Rank {}),
+    ]
+}
+
+#[test]
+fn selected_indices_are_in_range() {
+    for fitnesses in &[sorted_fitnesses(50), vec![-1.0, -2.0, -5.0], vec![0.0; 10], vec![3.0]] {
+        for strategy in strategies() {
+            let selected = strategy.select(fitnesses, 200);
+            assert_eq!(selected.len(), 200);
+            assert!(selected.iter().all(|index| *index < fitnesses.len()));
+        }
+    }
+}
+
+#[test]
+fn nothing_to_select_from() {
+    for strategy in strategies() {
+        assert!(strategy.select(&[], 10).is_empty());
+    }
+}
+
+#[test]
+fn truncation_only_picks_survivors() {
+    let selected = Truncation::new(0.1).select(&sorted_fitnesses(100), 1000);
+
+    assert!(selected.iter().all(|index| *index < 10));
+    assert!((0..10).all(|index| selected.contains(&index)));
+}
+
+#[test]
+fn tournament_size_sets_pressure() {
+    let fitnesses = sorted_fitnesses(100);
+    let mean = |selected: Vec<usize>| selected.iter().sum::<usize>() as f32 / selected.len() as f32;
+
+    // A tournament of one is a uniform pick, a large one nearly always finds the fittest
+    let uniform = mean(Tournament::new(1).select(&fitnesses, 2000));
+    let strong = mean(Tournament::new(20).select(&fitnesses, 2000));
+    assert!(uniform > 35.0 && uniform < 65.0);
+    assert!(strong < 10.0);
+}
+
+#[test]
+fn stochastic_universal_sampling_is_proportional() {
+    // With evenly spaced pointers every genome gets its expected share up to one pick
+    let fitnesses = vec![4.0, 3.0, 2.0, 1.0];
+    let selected = StochasticUniversalSampling {}.select(&fitnesses, 100);
+
+    for (index, fitness) in fitnesses.iter().enumerate() {
+        let picks = selected.iter().filter(|i| **i == index).count() as f32;
+        assert!((picks - fitness * 10.0).abs() <= 1.0);
+    }
+}
+
+#[test]
+fn negative_fitnesses_favour_the_fittest() {
+    let fitnesses = vec![-1.0, -50.0, -100.0];
+    let selected = Roulette {}.select(&fitnesses, 1000);
+
+    // The least fit genome has a weight of zero, the others are shifted up to 99 and 50
+    assert!(!selected.contains(&2));
+    assert!(selected.iter().filter(|i| **i == 0).count() > 550);
+}
+
+#[test]
+fn unusable_fitnesses_fall_back_to_uniform() {
+    // Too small to space 200 pointers apart
+    let tiny = vec![f32::from_bits(1); 3];
+    for fitnesses in &[
+        vec![f32::NAN, 1.0, 0.5],
+        vec![f32::INFINITY, 1.0, 0.5],
+        vec![2.0, 1.0, f32::NEG_INFINITY],
+        tiny,
+    ] {
+        for strategy in strategies() {
+            let selected = strategy.select(fitnesses, 200);
+            assert_eq!(selected.len(), 200);
+            assert!(selected.iter().all(|index| *index < fitnesses.len()));
+        }
+    }
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::evaluated;
+use neat_java::evaluator::selection::Truncation;
+use neat_java::Config;
+use neat_java::Species;
+
+#[test]
+fn members_are_selected_within_their_species() {
+    let mut config = Config::new(40, 1);
+    config.dt = 0.05;
+    let evaluator = evaluated(&config, 1);
+
+    let results = evaluator.get_last_generation_results();
+    for species in evaluator.get_species() {
+        for _ in 0..10 {
+            let member = species.select_member(&Truncation::new(1.0), results);
+            assert!(species.get_members().contains(&member));
+        }
+
+        // Only the champion survives a truncation this strict
+        let champion = species.select_member(&Truncation::new(0.01), results);
+        assert_eq!(Some(champion), species.get_champion());
+    }
+}
+
+#[test]
+fn offspring_follow_the_shared_fitness() {
+    let mut config = Config::new(40, 1);
+    config.dt = 0.05;
+    let evaluator = evaluated(&config, 1);
+
+    let results = evaluator.get_last_generation_results();
+    let species = evaluator.get_species();
+    let counts = Species::offspring_counts(species, results, 100, 0);
+    assert_eq!(counts.iter().sum::<usize>(), 100);
+
+    // Every species gets its mean fitness, shifted above zero, as a share of the children
+    let lowest = results
+        .iter()
+        .map(|result| result.get_fitness())
+        .fold(0.0, f32::min);
+    let means: Vec<f32> = species
+        .iter()
+        .map(|s| {
+            s.get_members()
+                .iter()
+                .map(|member| results[*member].get_fitness() - lowest)
+                .sum::<f32>()
+                / s.get_members().len() as f32
+        })
+        .collect();
+    let total: f32 = means.iter().sum();
+    for (count, mean) in counts.iter().zip(&means) {
+        assert!((*count as f32 - mean / total * 100.0).abs() < 1.0);
+    }
+}
+
+#[test]
+fn interspecies_mates_come_from_other_species() {
+    // Always another species, and every other species is picked
+    let mut picked = [0; 5];
+    for _ in 0..1000 {
+        let mate_species = Species::choose_mate_species(2, 5, 1.0);
+        assert_ne!(mate_species, 2);
+        picked[mate_species] += 1;
+    }
+    assert!(picked.iter().enumerate().all(|(s, count)| (s == 2) == (*count == 0)));
+
+    // Never another species without mating across them, or without another species
+    for _ in 0..100 {
+        assert_eq!(Species::choose_mate_species(3, 5, 0.0), 3);
+        assert_eq!(Species::choose_mate_species(0, 1, 1.0), 0);
+    }
+}
+
+#[test]
+fn interspecies_mating_fills_the_population() {
+    let mut config = Config::new(40, 5);
+    config.dt = 0.05;
+    config.a_sexual_reproduction_rate = 0.0;
+    config.interspecies_mating_rate = 1.0;
+
+    let evaluator = evaluated(&config, 5);
+    assert_eq!(evaluator.get_genomes().len(), 40);
+    assert!(evaluator.get_species().len() > 1);
+}
+
+#[test]
+fn dynamic_threshold_steers_the_species_count() {
+    let mut config = Config::new(40, 20);
+    config.dt = 0.05;
+    config.dynamic_dt = true;
+    config.target_species_count = 4;
+    config.dt_step = 0.1;
+    config.dt_min = 0.05;
+    config.dt_max = 1.0;
+
+    let evaluator = evaluated(&config, 20);
+    let history = evaluator.get_history();
+    assert_eq!(history[0].compatibility_threshold, 0.05);
+
+    // Too many species raise the threshold, too few lower it, within the bounds
+    for window in history.windows(2) {
+        let threshold = window[0].compatibility_threshold;
+        let expected = match window[0].species_count {
+            count if count > 4 => threshold + 0.1,
+            count if count < 4 => threshold - 0.1,
+            _ => threshold,
+        };
+        assert!((window[1].compatibility_threshold - expected.clamp(0.05, 1.0)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn static_threshold() {
+    let mut config = Config::new(20, 5);
+    config.dt = 2.5;
+
+    let evaluator = evaluated(&config, 5);
+    assert!(evaluator
+        .get_history()
+        .iter()
+        .all(|statistics| statistics.compatibility_threshold == 2.5));
+    assert_eq!(evaluator.get_compatibility_threshold(), 2.5);
+}
+
+#[test]
+fn species_sizes_are_recorded() {
+    let mut config = Config::new(40, 1);
+    config.dt = 0.05;
+
+    let evaluator = evaluated(&config, 1);
+    let sizes: Vec<usize> = evaluator
+        .get_species()
+        .iter()
+        .map(|species| species.get_members().len())
+        .collect();
+
+    let statistics = &evaluator.get_history()[0];
+    assert_eq!(statistics.species_count, sizes.len());
+    assert_eq!(statistics.largest_species_size, *sizes.iter().max().unwrap());
+    assert_eq!(statistics.smallest_species_size, *sizes.iter().min().unwrap());
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use std::fs;
+use std::io;
+
+use common::WeightSum;
+use neat_java::storage::genome_from_text;
+use neat_java::storage::genome_to_text;
+use neat_java::Checkpoint;
+use neat_java::Config;
+use neat_java::Counter;
+use neat_java::Evaluator;
+use neat_java::Genome;
+use neat_java::SearchPhase;
+
+/// A path in the temporary directory that no other test uses
+fn temporary_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("neat-{}-{}", std::process::id(), name))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn checkpoint() -> Checkpoint {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome =
+        Genome::with_hidden_layer(3, 2, 2, &mut node_innovation, &mut connection_innovation);
+    for (index, con) in genome.get_connection_genes_mut().iter_mut().enumerate() {
+        con.set_weight(index as f32 * 0.25 - 1.0);
+    }
+    genome.get_connection_genes_mut()[1].disable();
+    genome.set_mutation_step(0.3);
+
+    let mut config = Config::new(50, 200);
+    config.add_node_rate = 0.1;
+
+    Checkpoint {
+        config,
+        generation: 42,
+        node_innovation: node_innovation.get_current_innovation(),
+        connection_innovation: connection_innovation.get_current_innovation(),
+        task: String::from("xor"),
+        genomes: vec![
+            genome,
+            Genome::fully_connected(2, 1, &mut Counter::new(), &mut Counter::new()),
+        ],
+        novelty_archive: vec![vec![0.5, -1.25, 3.0], vec![0.1, 0.0, 2.75]],
+        compatibility_threshold: 2.4,
+        phase: SearchPhase::Simplifying,
+        complexity_ceiling: 31.5,
+        lowest_complexity: 28.25,
+        generations_since_lowest: 3,
+    }
+}
+
+#[test]
+fn genome_text_round_trip() {
+    let genome = &checkpoint().genomes[0];
+    assert_eq!(&genome_from_text(&genome_to_text(genome)).unwrap(), genome);
+}
+
+#[test]
+fn checkpoint_round_trip() {
+    let checkpoint = checkpoint();
+    let path = temporary_path("round-trip.checkpoint");
+
+    checkpoint.save(&path).unwrap();
+    let loaded = Checkpoint::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), checkpoint);
+}
+
+#[test]
+fn negative_generation_is_rejected() {
+    let path = temporary_path("negative.checkpoint");
+    fs::write(&path, "[checkpoint]\ngeneration = -1\n").unwrap();
+    let loaded = Checkpoint::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    let error = loaded.unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("line 2"));
+}
+
+#[test]
+fn resuming_keeps_the_tuned_state() {
+    let mut config = Config::new(30, 10);
+    config.dynamic_dt = true;
+    config.target_species_count = 100;
+    config.phased_search = true;
+
+    // The threshold shrinks every generation towards the unreachable species count
+    let mut evaluator = common::evaluated(&config, 5);
+    assert!(evaluator.get_compatibility_threshold() < config.dt);
+
+    // Switch to simplifying and stall once, so every part of the phase state differs from a fresh run
+    let complexity = evaluator.get_complexity_ceiling() + 1.0;
+    evaluator.update_phase(complexity);
+    evaluator.update_phase(complexity + 1.0);
+    assert_eq!(evaluator.get_phase(), SearchPhase::Simplifying);
+
+    let path = temporary_path("resume.checkpoint");
+    Checkpoint::new(&evaluator, &Counter::new(), &Counter::new(), "xor")
+        .save(&path)
+        .unwrap();
+    let loaded = Checkpoint::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    let mut resumed = Evaluator::new();
+    resumed.resume(&loaded.unwrap());
+    assert_eq!(resumed.get_generation(), 5);
+    assert_eq!(resumed.get_genomes(), evaluator.get_genomes());
+    assert_eq!(
+        resumed.get_compatibility_threshold(),
+        evaluator.get_compatibility_threshold()
+    );
+    assert_eq!(resumed.get_phase(), SearchPhase::Simplifying);
+    assert_eq!(
+        resumed.get_complexity_ceiling(),
+        evaluator.get_complexity_ceiling()
+    );
+    assert_eq!(resumed.get_lowest_complexity(), complexity);
+    assert_eq!(resumed.get_generations_since_lowest(), 1);
+
+    // The next generation is speciated with the tuned threshold
+    resumed
+        .evaluate_generation(
+            Box::new(WeightSum {}),
+            &mut Counter::starting_at(100),
+            &mut Counter::starting_at(100),
+        )
+        .unwrap();
+    assert_eq!(
+        resumed.get_history()[0].compatibility_threshold,
+        evaluator.get_compatibility_threshold()
+    );
+}
@@ -0,0 +1,129 @@
+use neat_java::Counter;
+use neat_java::Genome;
+use neat_java::NodeGeneType;
+
+fn nodes(genome: &Genome) -> Vec<(i32, NodeGeneType)> {
+    genome
+        .get_node_genes()
+        .iter()
+        .map(|node| (node.get_id(), node.get_type()))
+        .collect()
+}
+
+/// (innovation, in node, out node) of every connection
+fn connections(genome: &Genome) -> Vec<(i32, i32, i32)> {
+    genome
+        .get_connection_genes()
+        .iter()
+        .map(|con| (con.get_innovation(), con.get_in_node(), con.get_out_node()))
+        .collect()
+}
+
+#[test]
+fn minimal_numbering_is_stable() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let first = Genome::fully_connected(3, 2, &mut node_innovation, &mut connection_innovation);
+    assert_eq!(node_innovation.get_current_innovation(), 5);
+    assert_eq!(connection_innovation.get_current_innovation(), 6);
+
+    // Counters that have already handed out numbers don't change the numbering
+    let second = Genome::fully_connected(3, 2, &mut node_innovation, &mut connection_innovation);
+    let fresh = Genome::fully_connected(3, 2, &mut Counter::new(), &mut Counter::new());
+    assert_eq!(nodes(&first), nodes(&second));
+    assert_eq!(connections(&first), connections(&second));
+    assert_eq!(connections(&first), connections(&fresh));
+
+    // Inputs 1 to 3 and outputs 4 and 5, input i to output o is innovation o * 3 + i + 1
+    assert_eq!(
+        nodes(&first),
+        vec![
+            (1, NodeGeneType::INPUT),
+            (2, NodeGeneType::INPUT),
+            (3, NodeGeneType::INPUT),
+            (4, NodeGeneType::OUTPUT),
+            (5, NodeGeneType::OUTPUT),
+        ]
+    );
+    assert_eq!(
+        connections(&first),
+        vec![
+            (1, 1, 4),
+            (2, 2, 4),
+            (3, 3, 4),
+            (4, 1, 5),
+            (5, 2, 5),
+            (6, 3, 5)
+        ]
+    );
+}
+
+#[test]
+fn partial_topologies_use_the_same_numbering() {
+    let full = connections(&Genome::fully_connected(
+        3,
+        2,
+        &mut Counter::new(),
+        &mut Counter::new(),
+    ));
+
+    for _ in 0..20 {
+        let mut node_innovation = Counter::new();
+        let mut connection_innovation = Counter::new();
+        let sparse =
+            Genome::sparsely_connected(3, 2, 0.5, &mut node_innovation, &mut connection_innovation);
+        assert_eq!(sparse.get_connection_genes().len(), 3);
+        assert!(connections(&sparse).iter().all(|con| full.contains(con)));
+        assert_eq!(connection_innovation.get_current_innovation(), 6);
+
+        let fs_neat = Genome::fs_neat(3, 2, &mut Counter::new(), &mut Counter::new());
+        assert_eq!(fs_neat.get_connection_genes().len(), 1);
+        assert!(full.contains(&connections(&fs_neat)[0]));
+    }
+
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let unconnected = Genome::unconnected(3, 2, &mut node_innovation, &mut connection_innovation);
+    assert!(unconnected.get_connection_genes().is_empty());
+    assert_eq!(unconnected.get_node_genes().len(), 5);
+    assert_eq!(node_innovation.get_current_innovation(), 5);
+    assert_eq!(connection_innovation.get_current_innovation(), 6);
+}
+
+#[test]
+fn hidden_layer_numbering_is_stable() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let first =
+        Genome::with_hidden_layer(3, 2, 4, &mut node_innovation, &mut connection_innovation);
+    assert_eq!(node_innovation.get_current_innovation(), 9);
+    assert_eq!(connection_innovation.get_current_innovation(), 26);
+
+    let second =
+        Genome::with_hidden_layer(3, 2, 4, &mut node_innovation, &mut connection_innovation);
+    assert_eq!(nodes(&first), nodes(&second));
+    assert_eq!(connections(&first), connections(&second));
+
+    // Hidden nodes 6 to 9 follow the outputs
+    let hidden: Vec<i32> = nodes(&first)
+        .into_iter()
+        .filter(|(_, node_type)| *node_type == NodeGeneType::HIDDEN)
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(hidden, vec![6, 7, 8, 9]);
+
+    // Innovations 1 to 6 stay reserved for the input to output pairs, input i to hidden h
+    // is 3 * 2 + h * 3 + i + 1 and hidden h to output o continues from 19 with o * 4 + h
+    let mut expected = Vec::new();
+    for h in 0..4 {
+        for i in 0..3 {
+            expected.push((7 + h * 3 + i, i + 1, h + 6));
+        }
+    }
+    for o in 0..2 {
+        for h in 0..4 {
+            expected.push((19 + o * 4 + h, h + 6, o + 4));
+        }
+    }
+    assert_eq!(connections(&first), expected);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::RandomWeights;
+use neat_java::Config;
+use neat_java::ConnectionGene;
+use neat_java::Counter;
+use neat_java::Evaluator;
+use neat_java::FitnessGenomeProvider;
+use neat_java::Genome;
+use neat_java::NodeGene;
+use neat_java::NodeGeneType;
+use neat_java::WeightMutation;
+use neat_java::XorTask;
+
+/// Two inputs and a bias (1, 2, 3), an OR node (5) and an AND node (6) feeding the output (4)
+fn hand_built_solution() -> Genome {
+    let mut genome = Genome::new();
+    for id in 1..4 {
+        genome.add_node_gene(NodeGene::new(NodeGeneType::INPUT, id));
+    }
+    genome.add_node_gene(NodeGene::new(NodeGeneType::OUTPUT, 4));
+    genome.add_node_gene(NodeGene::new(NodeGeneType::HIDDEN, 5));
+    genome.add_node_gene(NodeGene::new(NodeGeneType::HIDDEN, 6));
+
+    let connections = [
+        (1, 5, 2.0),
+        (2, 5, 2.0),
+        (3, 5, -1.0),
+        (1, 6, 2.0),
+        (2, 6, 2.0),
+        (3, 6, -3.0),
+        (5, 4, 2.0),
+        (6, 4, -4.0),
+        (3, 4, -1.0),
+    ];
+    for (innovation, (in_node, out_node, weight)) in connections.iter().enumerate() {
+        genome.add_connection_gene(ConnectionGene::new(
+            *in_node,
+            *out_node,
+            *weight,
+            true,
+            innovation as i32 + 1,
+        ));
+    }
+
+    genome
+}
+
+#[test]
+fn hand_built_network_solves_xor() {
+    let genome = hand_built_solution();
+
+    assert!(XorTask::is_solved(&genome));
+
+    let fitness = XorTask::new().fitness_genome_evaluator(&genome);
+    assert!(fitness > 15.5 && fitness <= XorTask::MAX_FITNESS);
+}
+
+#[test]
+fn minimal_genome_cannot_solve_xor() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let mut genome = XorTask::starting_genome(&mut node_innovation, &mut connection_innovation);
+
+    assert_eq!(genome.get_node_genes().len(), XorTask::INPUT_COUNT + XorTask::OUTPUT_COUNT);
+    assert!(!XorTask::is_solved(&genome));
+
+    // Every output is 0.5 with zero weights, so the distance is 2
+    for con in genome.get_connection_genes_mut() {
+        con.set_weight(0.0);
+    }
+    let fitness = XorTask::new().fitness_genome_evaluator(&genome);
+    assert!((fitness - 4.0).abs() < 1e-5);
+}
+
+#[test]
+fn evolution_solves_xor() {
+    let mut node_innovation = Counter::new();
+    let mut connection_innovation = Counter::new();
+    let genome = XorTask::starting_genome(&mut node_innovation, &mut connection_innovation);
+
+    // Gaussian steps within bounds that keep the sigmoids from saturating, and a stronger weight term
+    // in the compatibility distance so new weight settings found their own species
+    let mut config = Config::new(150, 300);
+    config.weight_mutation = WeightMutation::Gaussian;
+    config.weight_min = -4.0;
+    config.weight_max = 4.0;
+    config.weight_replace_range = 4.0;
+    config.c3 = 1.0;
+    let mut evaluator = Evaluator::new();
+    evaluator.init(&config, &genome, Box::new(RandomWeights {}));
+
+    for _ in 0..config.get_generation_count() {
+        evaluator
+            .evaluate_generation(
+                Box::new(XorTask::new()),
+                &mut node_innovation,
+                &mut connection_innovation,
+            )
+            .unwrap();
+
+        if XorTask::is_solved(evaluator.get_fittest_genome().get_genome()) {
+            return;
+        }
+    }
+
+    panic!(
+        "XOR was not solved, best fitness {}",
+        evaluator.get_fittest_genome().get_fitness()
+    );
+}